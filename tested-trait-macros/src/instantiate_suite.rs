@@ -0,0 +1,1073 @@
+use std::collections::HashMap;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    braced,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    GenericArgument, Ident, LitInt, LitStr, Path, PathArguments, Type,
+};
+
+use super::{
+    substitute,
+    suite::{
+        peek_inclusion, Fixture, Included, Inclusion, Instantiation, Marker, Suite, SuiteParam,
+        SuiteTest, TypeList,
+    },
+    test_impl::ConcreteImpl,
+    AssociatedTestFnIdent, AssociatedTestProbeIdent, HandleAccessorIdent,
+};
+
+pub fn instantiate_suite(input: TokenStream) -> manyhow::Result<TokenStream> {
+    let ast = syn::parse2(input)?;
+    Ok(codegen(ast))
+}
+
+struct Ast {
+    suite: Suite,
+    request: Request,
+}
+
+/// What the suite macro was invoked to do with the suite
+enum Request {
+    /// Instantiate its tests for an implementation, from `#[test_impl]`
+    Instantiate(Instantiation),
+    /// Include it in another trait's suite, from the `#[tested_trait]` of a subtrait or of a
+    /// trait including the tests of a `#[trait_tests]` module
+    Include(Inclusion),
+}
+
+impl Parse for Ast {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let suite;
+        braced!(suite in input);
+        let request;
+        braced!(request in input);
+        Ok(Self {
+            suite: suite.parse()?,
+            request: if peek_inclusion(&request) {
+                Request::Include(request.parse()?)
+            } else {
+                Request::Instantiate(request.parse()?)
+            },
+        })
+    }
+}
+
+/// An associated test as run against one of the implementation's fixtures, if it takes
+/// `self`.
+struct Instance<'a> {
+    test: &'a SuiteTest,
+    /// `test`, or `test_u8` when instantiated with `u8` for a generic test
+    base: Ident,
+    /// The types representing the generic test's arguments, e.g. `(PhantomData<u8>,)`
+    test_args: Option<TokenStream>,
+    fixture: Option<&'a Fixture>,
+    /// `base`, or `base::fixture` if the implementation has several (named) fixtures
+    name: String,
+}
+
+/// The instances of `test`: one for each combination of its generic arguments (if any), for
+/// each fixture if it takes `self`.
+///
+/// Fails with a message if a generic parameter of the test has no list of arguments.
+fn instances_of<'a>(
+    test: &'a SuiteTest,
+    fixtures: &'a [Fixture],
+    types: &[TypeList],
+) -> Result<Vec<Instance<'a>>, String> {
+    let mut instantiations = vec![(Vec::new(), Vec::new())];
+    for param in &test.generics {
+        let ident = match param {
+            SuiteParam::Type { ident, .. } | SuiteParam::Const { ident, .. } => ident,
+        };
+        let list = (types.iter())
+            .find(|list| list.param == *ident)
+            .ok_or_else(|| {
+                format!(
+                    "the generic test `{}` has no types to be instantiated with for `{ident}`, \
+                     which must be listed with `types({ident} = [..])` in #[test_impl] or \
+                     #[tested_trait]",
+                    test.ident
+                )
+            })?;
+        instantiations = (instantiations.into_iter())
+            .flat_map(|(words, args)| {
+                list.args.iter().map(move |arg| {
+                    let mut words = words.clone();
+                    if !super::test_impl::spell_out(arg.to_token_stream(), &mut words) {
+                        let hash = super::fnv1a(&arg.to_token_stream().to_string());
+                        words.push(format!("{hash:016x}"));
+                    }
+                    let mut args = args.clone();
+                    args.push(match param {
+                        SuiteParam::Type { .. } => {
+                            quote! { ::core::marker::PhantomData<#arg> }
+                        }
+                        SuiteParam::Const { wrapper, .. } => quote! { #wrapper<#arg> },
+                    });
+                    (words, args)
+                })
+            })
+            .collect();
+    }
+    let mut instances = Vec::new();
+    for (words, args) in instantiations {
+        let (base, test_args) = if test.generics.is_empty() {
+            (test.ident.clone(), None)
+        } else {
+            let base = format_ident!("{}_{}", test.ident, words.join("_"));
+            (base, Some(quote! { (#(#args,)*) }))
+        };
+        if !test.fixture {
+            instances.push(Instance {
+                test,
+                name: base.to_string(),
+                base,
+                test_args,
+                fixture: None,
+            });
+            continue;
+        }
+        instances.extend(fixtures.iter().map(|fixture| Instance {
+            test,
+            name: match &fixture.name {
+                Some(fixture) => format!("{base}::{fixture}"),
+                None => base.to_string(),
+            },
+            base: base.clone(),
+            test_args: test_args.clone(),
+            fixture: Some(fixture),
+        }));
+    }
+    Ok(instances)
+}
+
+/// The function returning a fixture, which evaluates its expression anew for each test.
+fn fixture_fn(fixture: &Fixture) -> Ident {
+    match &fixture.name {
+        Some(name) => format_ident!("__tested_trait_fixture_{name}"),
+        None => format_ident!("__tested_trait_fixture"),
+    }
+}
+
+fn codegen(ast: Ast) -> TokenStream {
+    match ast.request {
+        Request::Instantiate(instantiation) => include_tests(ast.suite, instantiation),
+        Request::Include(inclusion) => include(ast.suite, inclusion),
+    }
+}
+
+/// Includes `suite` in the subtrait's, then passes the inclusion on to the next included
+/// trait's macro, or defines the subtrait's suite macro once all are included.
+fn include(suite: Suite, inclusion: Inclusion) -> TokenStream {
+    if let Some(message) = &suite.disabled {
+        return quote! { ::core::compile_error!(#message); };
+    }
+    let Inclusion {
+        attrs,
+        macro_name,
+        mut subtrait,
+        included,
+    } = inclusion;
+    let mut included = included.into_iter();
+    let path = (included.next()).expect("inclusions name at least one trait");
+    subtrait.included.push(Included {
+        path,
+        by_path: false,
+        suite,
+    });
+    let included = included.collect::<Punctuated<_, _>>();
+    if let Some(next) = included.first() {
+        let next_macro = bare_path(next);
+        let inclusion = Inclusion {
+            attrs,
+            macro_name,
+            subtrait,
+            included,
+        };
+        quote! { #next_macro! { #inclusion } }
+    } else {
+        quote! {
+            #(#attrs)*
+            macro_rules! #macro_name {
+                ($($instantiation:tt)*) => {
+                    ::tested_trait::__private::instantiate_suite! {
+                        { #subtrait }
+                        { $($instantiation)* }
+                    }
+                };
+            }
+        }
+    }
+}
+
+/// Explains why a suite whose tests aren't compiled can't be instantiated, which only testing the
+/// implementation requires, so that non-test builds are unaffected.
+fn disabled(message: &LitStr, in_integration_test: bool) -> TokenStream {
+    if in_integration_test {
+        quote! { ::core::compile_error!(#message); }
+    } else {
+        quote! {
+            #[cfg(test)]
+            ::core::compile_error!(#message);
+        }
+    }
+}
+
+/// The path to a trait without its generic arguments, which also names its suite macro.
+pub fn bare_path(trait_: &Path) -> Path {
+    let mut path = trait_.clone();
+    (path.segments)
+        .last_mut()
+        .expect("trait `Path`s contain at least one segment")
+        .arguments = PathArguments::None;
+    path
+}
+
+/// Includes the suites of the `#[trait_tests]` modules named by `#[test_impl]` in the trait's,
+/// passing the instantiation on to each module's macro in turn before instantiating it.
+///
+/// `suite` is the trait's suite when its own macro is expanded, and that of the first module
+/// in `instantiation.tests` afterwards.
+fn include_tests(suite: Suite, mut instantiation: Instantiation) -> TokenStream {
+    let subject = match instantiation.subject.take() {
+        None => suite,
+        Some(mut subject) => {
+            let path = instantiation.tests.remove(0);
+            subject.included.push(Included {
+                path,
+                by_path: true,
+                suite,
+            });
+            *subject
+        }
+    };
+    match instantiation.tests.first() {
+        Some(next) => {
+            let next_macro = bare_path(next);
+            instantiation.subject = Some(Box::new(subject));
+            quote! { #next_macro! { #instantiation } }
+        }
+        None => instantiate(subject, instantiation),
+    }
+}
+
+/// Asserts that the implementer reached through `marker` implements `trait_`, which also ensures
+/// the trait is considered used when it has no associated tests.
+fn assert_implemented(trait_: &Path, marker: &TokenStream) -> TokenStream {
+    quote! {
+        const _: fn() = || {
+            fn assert_implemented<T: ?::core::marker::Sized + #trait_>(
+                _: ::core::marker::PhantomData<*const T>,
+            ) {
+            }
+            assert_implemented(#marker);
+        };
+    }
+}
+
+fn instantiate(mut suite: Suite, instantiation: Instantiation) -> TokenStream {
+    if let Some(message) = &suite.disabled {
+        return disabled(message, instantiation.in_integration_test);
+    }
+    let Instantiation {
+        in_integration_test,
+        fixtures,
+        types,
+        tests: _,
+        subject: _,
+        with,
+        checked,
+        name,
+        concrete:
+            ConcreteImpl {
+                implementer,
+                colon: _,
+                trait_,
+            },
+    } = instantiation;
+    let trait_name = &suite.trait_name;
+    let marker = (checked.as_ref()).map_or_else(
+        || quote! { ::core::marker::PhantomData::<*const #implementer> },
+        ToTokens::to_token_stream,
+    );
+    let assert_implemented = assert_implemented(&trait_, &marker);
+    if let Some(list) = (types.iter()).find(|list| !has_generic_param(&suite, &list.param)) {
+        let message = format!(
+            "no associated test of `{trait_name}` has a generic parameter `{}`",
+            list.param
+        );
+        return quote! { ::core::compile_error!(#message); };
+    }
+    let fixture_fns = fixtures.iter().map(|fixture| {
+        let fixture_fn = fixture_fn(fixture);
+        let expr = &fixture.expr;
+        quote! {
+            #[allow(dead_code)]
+            fn #fixture_fn() -> #implementer {
+                #expr
+            }
+        }
+    });
+    let fixture_fns = quote! { #(#fixture_fns)* };
+    let suite_trait = match suite_trait(&suite, with.as_ref(), &trait_) {
+        Ok(suite_trait) => suite_trait,
+        Err(message) => return quote! { ::core::compile_error!(#message); },
+    };
+    let aliases = if in_integration_test {
+        Vec::new()
+    } else {
+        alias_included_paths(&mut suite, &name)
+    };
+    let instantiator = Instantiator {
+        in_integration_test,
+        fixtures: &fixtures,
+        types: &types,
+        fixture_fns: &fixture_fns,
+        implementer: &implementer,
+        marker: &marker,
+        trait_: &suite_trait,
+    };
+    let mut suite_impls = Vec::new();
+    let handle = Handle::Path(suite_trait.clone());
+    let tests = match instantiator.instantiate(
+        &suite,
+        &suite_trait,
+        &handle,
+        &instantiator.impl_name(&trait_),
+        std::slice::from_ref(&name),
+        &mut suite_impls,
+    ) {
+        Ok(tests) => tests,
+        Err(message) => return quote! { ::core::compile_error!(#message); },
+    };
+    if in_integration_test {
+        // A statement rather than an expression, which lints would expect to be followed by `;`
+        quote! {
+            {
+                #fixture_fns
+                #assert_implemented
+                #(#suite_impls)*
+                #tests
+            };
+        }
+    } else {
+        quote! {
+            #(#suite_impls)*
+            #(#aliases)*
+
+            #[cfg(test)]
+            #[doc(hidden)]
+            #[allow(non_snake_case)]
+            mod #name {
+                #[allow(unused_imports)]
+                use super::*;
+
+                #assert_implemented
+                #fixture_fns
+                #tests
+            }
+        }
+    }
+}
+
+/// Whether a test of `suite` or of the suites it includes has the generic parameter `param`.
+fn has_generic_param(suite: &Suite, param: &Ident) -> bool {
+    (suite.tests.iter())
+        .flat_map(|test| &test.generics)
+        .any(|generic| match generic {
+            SuiteParam::Type { ident, .. } | SuiteParam::Const { ident, .. } => ident == param,
+        })
+        || (suite.included.iter()).any(|included| has_generic_param(&included.suite, param))
+}
+
+/// The trait whose handle the suite's tests are reached through: the implemented `trait_`,
+/// or its remote definition `with` (or sharing its path) when the suite is a remote
+/// definition's.
+///
+/// A remote definition's tests are reached through its own handle and instantiated with its
+/// own generic arguments, but reported as tests of the foreign trait. Fails with a message if
+/// `with` doesn't name a remote definition.
+fn suite_trait(suite: &Suite, with: Option<&Path>, trait_: &Path) -> Result<Path, String> {
+    match (&suite.remote, with) {
+        (Some(remote), with) => {
+            let definition = with.cloned().unwrap_or_else(|| bare_path(trait_));
+            remote_trait(&definition, &suite.params, remote, trait_)
+        }
+        (None, Some(with)) => Err(format!(
+            "#[test_impl(with = ..)] must name a remote definition, i.e. a \
+             #[tested_trait(remote = ..)] trait, which `{}` is not",
+            super::pretty_print(with)
+        )),
+        (None, None) => Ok(trait_.clone()),
+    }
+}
+
+/// Replaces the paths of the `#[trait_tests]` modules included by `#[test_impl]` with
+/// aliases, returning their `use` items.
+///
+/// Their tests are grouped in modules named after them, which would shadow the paths naming
+/// them, so they're reached through aliases instead.
+fn alias_included_paths(suite: &mut Suite, name: &Ident) -> Vec<TokenStream> {
+    let mut aliases = Vec::new();
+    for included in (suite.included.iter_mut()).filter(|included| included.by_path) {
+        let alias = format_ident!("__tested_trait_{name}_{}", included.suite.trait_name);
+        let path = std::mem::replace(&mut included.path, alias.clone().into());
+        aliases.push(quote! {
+            #[doc(hidden)]
+            #[allow(unused_imports)]
+            use #path as #alias;
+        });
+    }
+    aliases
+}
+
+/// The remote `definition` of the foreign trait `remote`, with the generic arguments that
+/// `trait_` (an instantiation of `remote`) has in place of the definition's parameters, e.g.
+/// `ServiceTests<Req>` for `Service<Req>` with `remote = Service<Request>`.
+///
+/// Fails with a message if a parameter of the definition without a default can't be matched up
+/// with an argument.
+fn remote_trait(
+    definition: &Path,
+    params: &[SuiteParam],
+    remote: &Path,
+    trait_: &Path,
+) -> Result<Path, String> {
+    let args = |path: &Path| match path.segments.last().map(|segment| &segment.arguments) {
+        Some(PathArguments::AngleBracketed(args)) => (args.args.iter())
+            .filter(|arg| !matches!(arg, GenericArgument::Lifetime(_)))
+            .cloned()
+            .collect(),
+        _ => Vec::new(),
+    };
+    let matched = (args(remote).into_iter())
+        .zip(args(trait_))
+        .filter_map(|(param, arg)| match param {
+            GenericArgument::Type(Type::Path(ty)) if ty.qself.is_none() => {
+                Some((ty.path.get_ident()?.to_string(), arg))
+            }
+            _ => None,
+        })
+        .collect::<HashMap<_, _>>();
+    let args = (params.iter())
+        .map(|param| match param {
+            SuiteParam::Type { ident, default } => (ident, default.is_some()),
+            SuiteParam::Const { ident, default, .. } => (ident, default.is_some()),
+        })
+        .map(|(ident, has_default)| (ident, has_default, matched.get(&ident.to_string())))
+        .collect::<Vec<_>>();
+    // Arguments can only be omitted after the last one that's matched up
+    let given = (args.iter())
+        .rposition(|(_, _, arg)| arg.is_some())
+        .map_or(0, |last| last + 1);
+    if let Some((ident, ..)) = (args.iter().enumerate())
+        .find(|(i, (_, has_default, arg))| arg.is_none() && (*i < given || !has_default))
+        .map(|(_, arg)| arg)
+    {
+        return Err(format!(
+            "the generic parameter `{ident}` of `{}` can't be matched up with an argument of \
+             `{}`, as it isn't one of the arguments of `{}`",
+            super::pretty_print(definition),
+            super::pretty_print(trait_),
+            super::pretty_print(remote)
+        ));
+    }
+    let args = args[..given].iter().filter_map(|(_, _, arg)| *arg);
+    let mut path = bare_path(definition);
+    if given > 0 {
+        (path.segments)
+            .last_mut()
+            .expect("trait `Path`s contain at least one segment")
+            .arguments = PathArguments::AngleBracketed(syn::parse_quote! { <#(#args),*> });
+    }
+    Ok(path)
+}
+
+/// How the handle of a trait, through which its tests are reached, is found: as the return
+/// type of the trait's `__tested_trait_handle_*` accessor.
+enum Handle {
+    /// Through the implementation's accessor of the trait at the path
+    Path(Path),
+    /// Through the accessor of a trait whose tests the instantiated trait includes, which is
+    /// only found on a type parameter bounded by the instantiated trait
+    Included,
+}
+
+/// The parts of a `#[test_impl]` invocation shared by the tests of the trait and those of the
+/// suites it includes.
+struct Instantiator<'a> {
+    in_integration_test: bool,
+    fixtures: &'a [Fixture],
+    types: &'a [TypeList],
+    fixture_fns: &'a TokenStream,
+    implementer: &'a Type,
+    /// Evaluates to a `PhantomData<*const Implementer>`, which tests reach the implementer's
+    /// handle through, checking it instantiates the annotated generic impl if there is one
+    marker: &'a TokenStream,
+    /// The instantiated trait
+    trait_: &'a Path,
+}
+
+impl Instantiator<'_> {
+    /// The name the implementation of `trait_` is reported by, e.g. `Foo<u8> for Bar`.
+    fn impl_name(&self, trait_: &Path) -> String {
+        format!(
+            "{} for {}",
+            super::pretty_print(trait_),
+            super::pretty_print(self.implementer)
+        )
+    }
+
+    /// Instantiates the tests of `suite` and of the suites it includes, whose trait is
+    /// `trait_` (with the generic arguments it's instantiated with) and whose tests are
+    /// reported as those of `impl_name` and generated in `module` when run by the test
+    /// harness.
+    ///
+    /// Adds the implementations of `SuiteOf` for the trait and the included traits to
+    /// `suite_impls`. Fails with a message if the suite can't be instantiated.
+    fn instantiate(
+        &self,
+        suite: &Suite,
+        trait_: &Path,
+        handle: &Handle,
+        impl_name: &str,
+        module: &[Ident],
+        suite_impls: &mut Vec<TokenStream>,
+    ) -> Result<TokenStream, String> {
+        let Self {
+            in_integration_test,
+            fixtures,
+            types,
+            implementer,
+            ..
+        } = self;
+        let trait_name = &suite.trait_name;
+        let Some((args, substitutions)) = suite_args(&suite.params, trait_, implementer) else {
+            return Err(format!(
+                "the generic arguments of `{}` don't match its parameters",
+                super::pretty_print(trait_)
+            ));
+        };
+        if let (Some(test), true) = (
+            suite.tests.iter().find(|test| test.fixture),
+            fixtures.is_empty(),
+        ) {
+            return Err(format!(
+                "the associated test `{}` of `{}` takes `self`, so #[test_impl] must supply \
+                 the values it's run against with `fixture = ..` or `fixtures(name = .., ..)`",
+                test.ident, trait_name
+            ));
+        }
+        // Generic tests are instantiated with the implementation's types, or else the trait's
+        let types = (types.iter())
+            .chain(suite.types.iter().filter(|list| {
+                !types
+                    .iter()
+                    .any(|overridden| overridden.param == list.param)
+            }))
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut instances = Vec::new();
+        for test in &suite.tests {
+            instances.extend(instances_of(test, fixtures, &types)?);
+        }
+        let instantiation = SuiteInstantiation {
+            instantiator: self,
+            trait_name,
+            handle,
+            args: &args,
+            impl_name,
+        };
+        if let Some(id) = &suite.accessor_id {
+            suite_impls.push(instantiation.suite_impl(id, &instances, module));
+        }
+        // The included traits' tests are reached through their handles' accessors on the
+        // instantiated trait (unless named by `#[test_impl]`), with generic arguments in terms
+        // of the including trait's. Out-of-line tests are reported as tests of the trait.
+        let mut included_tests = Vec::with_capacity(suite.included.len());
+        for Included {
+            path,
+            by_path,
+            suite,
+        } in &suite.included
+        {
+            let path = substitute(path.to_token_stream(), &substitutions);
+            let path: Path = syn::parse2(path).map_err(|err| err.to_string())?;
+            let mut module = module.to_vec();
+            module.push(suite.trait_name.clone());
+            let handle = if *by_path {
+                Handle::Path(path.clone())
+            } else {
+                Handle::Included
+            };
+            let impl_name = if suite.out_of_line {
+                impl_name.to_owned()
+            } else {
+                self.impl_name(&path)
+            };
+            let tests =
+                self.instantiate(suite, &path, &handle, &impl_name, &module, suite_impls)?;
+            included_tests.push((&suite.trait_name, tests));
+        }
+        if *in_integration_test {
+            let included_tests = included_tests.into_iter().map(|(_, tests)| tests);
+            instantiation.run_inline(suite, &instances, included_tests)
+        } else {
+            Ok(instantiation.test_fns(&instances, &included_tests))
+        }
+    }
+}
+
+/// The instantiation of a suite's tests, which are reached through the handle of the trait
+/// named `trait_name`.
+struct SuiteInstantiation<'a> {
+    instantiator: &'a Instantiator<'a>,
+    trait_name: &'a Ident,
+    handle: &'a Handle,
+    /// The types representing the trait's generic arguments, passed to its handle
+    args: &'a TokenStream,
+    /// The name the implementation is reported by
+    impl_name: &'a str,
+}
+
+impl SuiteInstantiation<'_> {
+    /// Evaluates the expression built by `call` from an expression evaluating to the handle,
+    /// whose result is of type `ty`.
+    fn call_handle(
+        &self,
+        ty: &TokenStream,
+        call: impl FnOnce(TokenStream) -> TokenStream,
+    ) -> TokenStream {
+        let Instantiator { marker, trait_, .. } = self.instantiator;
+        let accessor = HandleAccessorIdent(self.trait_name);
+        match self.handle {
+            Handle::Path(path) => call(quote! {
+                ::tested_trait::__private::handle(#marker, <_ as #path>::#accessor)
+            }),
+            Handle::Included => {
+                let call = call(quote! {
+                    ::tested_trait::__private::handle(
+                        ::core::marker::PhantomData::<*const __Self>,
+                        __Self::#accessor,
+                    )
+                });
+                quote! {{
+                    fn reach<__Self: ?::core::marker::Sized + #trait_>(
+                        _: ::core::marker::PhantomData<*const __Self>,
+                    ) -> #ty {
+                        #[allow(unused_imports)]
+                        use ::tested_trait::__private::Unsatisfied as _;
+                        #call
+                    }
+                    reach(#marker)
+                }}
+            }
+        }
+    }
+
+    /// Evaluates to the test's function, which runs it against its fixture (if any).
+    ///
+    /// The fixture is converted into the implementer with `Into`, which is the identity, but
+    /// leaves the implementer uninferred rather than mismatched when its check fails.
+    fn test_fn(&self, instance: &Instance) -> TokenStream {
+        let Self { args, .. } = self;
+        let marker = self.instantiator.marker;
+        let test_fn_ident = AssociatedTestFnIdent(&instance.test.ident);
+        let test_args = instance.test_args.iter();
+        self.call_handle(&quote! { fn() }, |handle| {
+            let test_fn = quote! {
+                #handle.#test_fn_ident(
+                    #marker,
+                    ::core::marker::PhantomData::<(#args, #(#test_args)*)>,
+                )
+            };
+            if let Some(fixture) = instance.fixture {
+                let fixture_fn = fixture_fn(fixture);
+                quote! {
+                    (|| (#test_fn)(::core::convert::Into::into(#fixture_fn()))) as fn()
+                }
+            } else {
+                test_fn
+            }
+        })
+    }
+
+    /// Calls `method` on the test's probe for `bound`, reached through `handle`.
+    fn probe_call(
+        &self,
+        instance: &Instance,
+        bound: usize,
+        method: &str,
+        handle: &TokenStream,
+    ) -> TokenStream {
+        let Self { args, .. } = self;
+        let marker = self.instantiator.marker;
+        let probe_fn_ident = AssociatedTestProbeIdent(&instance.test.ident);
+        let method = Ident::new(method, Span::call_site());
+        let test_args = instance.test_args.iter();
+        quote! {
+            (&#handle.#probe_fn_ident(
+                #marker,
+                ::core::marker::PhantomData::<(
+                    #args,
+                    #(#test_args,)*
+                    ::tested_trait::__private::ConstUsize<#bound>,
+                )>,
+            )).#method()
+        }
+    }
+
+    /// Evaluates to the test's function, or why it was skipped.
+    fn select_test(&self, instance: &Instance) -> TokenStream {
+        let Some(bounds) = &instance.test.optional_bounds else {
+            let test_fn = self.test_fn(instance);
+            return quote! { ::core::result::Result::Ok(#test_fn) };
+        };
+        let bounds = bounds.iter().enumerate().map(|(i, bound)| {
+            let satisfied = self.call_handle(&quote! { bool }, |handle| {
+                self.probe_call(instance, i + 1, "__tested_trait_satisfied", &handle)
+            });
+            quote! { (#bound, #satisfied) }
+        });
+        let ty = quote! { ::core::option::Option<fn()> };
+        let test_fn = if let Some(fixture) = instance.fixture {
+            // The probe's function is only called if it's provided, by which point the
+            // fixture's type is known
+            let marker = self.instantiator.marker;
+            let fixture_fn = fixture_fn(fixture);
+            self.call_handle(&ty, |handle| {
+                let fixture_test_fn =
+                    self.probe_call(instance, 0, "__tested_trait_fixture_test_fn", &handle);
+                let fixture_test_fn = quote! {
+                    ::tested_trait::__private::fixture_test_fn(#marker, #fixture_test_fn)
+                };
+                quote! {
+                    #fixture_test_fn.map(|_| {
+                        (|| (#fixture_test_fn.unwrap())(::core::convert::Into::into(#fixture_fn())))
+                            as fn()
+                    })
+                }
+            })
+        } else {
+            self.call_handle(&ty, |handle| {
+                self.probe_call(instance, 0, "__tested_trait_test_fn", &handle)
+            })
+        };
+        quote! {{
+            use ::tested_trait::__private::Unsatisfied as _;
+            ::tested_trait::__private::select_optional(#test_fn, &[#(#bounds),*])
+        }}
+    }
+
+    /// Implements `SuiteOf` for the implementation, which exposes the tests through the
+    /// trait's `tested_trait_suite()` accessor and registers them for `tested_trait::harness`.
+    fn suite_impl(&self, id: &LitInt, instances: &[Instance], module: &[Ident]) -> TokenStream {
+        let Self {
+            trait_name,
+            args,
+            impl_name,
+            ..
+        } = self;
+        let Instantiator {
+            in_integration_test,
+            fixture_fns,
+            implementer,
+            ..
+        } = self.instantiator;
+        let test_cases = instances.iter().map(|instance| {
+            let Instance { test, name, .. } = instance;
+            let kind =
+                (test.kind.clone()).unwrap_or_else(|| Ident::new("Standard", Span::call_site()));
+            let ignored = test.ignore.is_present();
+            let ignore_reason = super::quote_option(test.ignore.message());
+            let timeout = timeout(test);
+            let serial = test.serial;
+            let select_test = self.select_test(instance);
+            quote! {
+                ::tested_trait::TestCase {
+                    name: #name,
+                    kind: ::tested_trait::TestKind::#kind,
+                    ignored: #ignored,
+                    ignore_reason: #ignore_reason,
+                    serial: #serial,
+                    run: || ::tested_trait::__private::run_case(
+                        #name,
+                        #impl_name,
+                        #timeout,
+                        #serial,
+                        #select_test,
+                    ),
+                }
+            }
+        });
+        let module_name = (module.iter())
+            .map(Ident::to_string)
+            .collect::<Vec<_>>()
+            .join("::");
+        // Registers the tests for `tested_trait::harness`, unless they're run inline
+        let register = (cfg!(feature = "harness") && !in_integration_test).then(|| {
+            quote! {
+                ::tested_trait::__private::inventory::submit! {
+                    ::tested_trait::__private::RegisteredSuite {
+                        module_path: ::core::module_path!(),
+                        module: #module_name,
+                        trait_name: ::core::stringify!(#trait_name),
+                        impl_name: #impl_name,
+                        tests: <#implementer as ::tested_trait::__private::SuiteOf<
+                            #id,
+                            #args,
+                            __TestedTraitSuite,
+                        >>::tests,
+                    }
+                }
+            }
+        });
+        quote! {
+            const _: () = {
+                #[doc(hidden)]
+                pub struct __TestedTraitSuite;
+
+                #fixture_fns
+
+                #[allow(non_local_definitions)]
+                impl ::tested_trait::__private::SuiteOf<#id, #args, __TestedTraitSuite>
+                    for #implementer
+                {
+                    fn tests() -> &'static [::tested_trait::TestCase] {
+                        const TESTS: &[::tested_trait::TestCase] = &[#(#test_cases),*];
+                        TESTS
+                    }
+                }
+
+                #register
+            };
+        }
+    }
+
+    /// Evaluates to the instance's `Test`.
+    fn test(&self, instance: &Instance) -> TokenStream {
+        let Instance { test, name, .. } = instance;
+        let select_test = self.select_test(instance);
+        let ignore = match &test.ignore {
+            Marker::WithMessage(reason) => {
+                quote! { ::tested_trait::__private::Ignore::WithReason(#reason) }
+            }
+            Marker::Present => quote! { ::tested_trait::__private::Ignore::Yes },
+            Marker::Absent => quote! { ::tested_trait::__private::Ignore::No },
+        };
+        let timeout = timeout(test);
+        let serial = test.serial;
+        quote! {
+            ::tested_trait::__private::Test {
+                name: #name,
+                ignore: #ignore,
+                timeout: #timeout,
+                serial: #serial,
+                run: #select_test,
+            }
+        }
+    }
+
+    /// Runs the tests inline, in an integration test, followed by `included_tests`.
+    fn run_inline(
+        &self,
+        suite: &Suite,
+        instances: &[Instance],
+        included_tests: impl IntoIterator<Item = TokenStream>,
+    ) -> Result<TokenStream, String> {
+        let impl_name = self.impl_name;
+        let trait_name = &suite.trait_name;
+        let tests = instances.iter().map(|instance| self.test(instance));
+        let parallel = suite.parallel;
+        let run_suite = if cfg!(feature = "std") {
+            quote! {
+                ::tested_trait::__private::run_suite(
+                    ::core::stringify!(#trait_name),
+                    #impl_name,
+                    #parallel,
+                    &[#(#tests),*],
+                );
+            }
+        } else if let Some(test) = (suite.tests.iter()).find(|test| test.should_panic.is_present())
+        {
+            // Without `std`, only the test harness can catch panics
+            return Err(format!(
+                "#[should_panic] test `{}` can't be run #[in_integration_test] without \
+                 tested-trait's `std` feature",
+                test.ident
+            ));
+        } else {
+            quote! {
+                ::tested_trait::__private::run_inline(#impl_name, &[#(#tests),*]);
+            }
+        };
+        let included_tests = included_tests.into_iter();
+        Ok(quote! {
+            #run_suite
+            #(#included_tests)*
+        })
+    }
+
+    /// Generates a `#[test]` for each instance, followed by the tests of the included traits
+    /// grouped in modules named after them.
+    fn test_fns(
+        &self,
+        instances: &[Instance],
+        included_tests: &[(&Ident, TokenStream)],
+    ) -> TokenStream {
+        let test_fns = (instances.chunk_by(|a, b| a.base == b.base)).map(|instances| {
+            match instances {
+                [instance] if instance.fixture.is_none_or(|f| f.name.is_none()) => {
+                    self.test_fn_item(instance, &instance.base)
+                }
+                // Tests run against several fixtures are grouped in a module named after the
+                // test, with a test named after each fixture
+                instances => {
+                    let ident = &instances[0].base;
+                    let test_fns = instances.iter().map(|instance| {
+                        let fixture = instance.fixture.and_then(|f| f.name.as_ref());
+                        self.test_fn_item(instance, fixture.expect("fixtures are named"))
+                    });
+                    quote! {
+                        mod #ident {
+                            #[allow(unused_imports)]
+                            use super::*;
+
+                            #(#test_fns)*
+                        }
+                    }
+                }
+            }
+        });
+        let included_tests = included_tests.iter().map(|(trait_name, tests)| {
+            quote! {
+                mod #trait_name {
+                    #[allow(unused_imports)]
+                    use super::*;
+
+                    #tests
+                }
+            }
+        });
+        quote! {
+            #(#test_fns)*
+            #(#included_tests)*
+        }
+    }
+
+    /// Generates the `#[test]` named `ident` that runs `instance`.
+    fn test_fn_item(&self, instance: &Instance, ident: &Ident) -> TokenStream {
+        let impl_name = self.impl_name;
+        let test = instance.test;
+        let run_test = if cfg!(feature = "std") {
+            // Also records the outcome in the report requested by `TESTED_TRAIT_REPORT`, if any
+            let trait_name = self.trait_name;
+            let should_panic = test.should_panic.is_present();
+            let expected_panic = super::quote_option(test.should_panic.message());
+            let test = self.test(instance);
+            quote! {
+                ::tested_trait::__private::run_reported(
+                    ::core::stringify!(#trait_name),
+                    #impl_name,
+                    #should_panic,
+                    #expected_panic,
+                    &#test,
+                )
+            }
+        } else {
+            // Timeouts require threads, so they have no effect without `std`
+            let name = &instance.name;
+            let run_test = if test.optional_bounds.is_some() {
+                let select_test = self.select_test(instance);
+                quote! {
+                    ::tested_trait::__private::run_or_skip(
+                        #impl_name,
+                        #name,
+                        #select_test,
+                    )
+                }
+            } else {
+                let test_fn = self.test_fn(instance);
+                quote! { (#test_fn)() }
+            };
+            let serial = test.serial;
+            quote! { ::tested_trait::__private::run_locked(#serial, || #run_test) }
+        };
+        let ignore = test.ignore.to_attr("ignore");
+        let should_panic = test.should_panic.to_attr("should_panic");
+        quote! {
+            #[test]
+            #ignore
+            #should_panic
+            fn #ident() {
+                #run_test
+            }
+        }
+    }
+}
+
+/// Evaluates to the test's timeout, if any.
+fn timeout(test: &SuiteTest) -> TokenStream {
+    super::quote_option(
+        (test.timeout_ms.as_ref()).map(|ms| quote! { ::core::time::Duration::from_millis(#ms) }),
+    )
+}
+
+/// Lowers the trait's generic arguments in `trait_` to the types passed to its handle (and
+/// `SuiteOf`'s `Args`), filling in the defaults of any that are omitted.
+///
+/// Also returns the arguments by the name of their parameter (and the implementer by `Self`),
+/// which the trait's supertraits' arguments may refer to.
+///
+/// Returns `None` if the arguments can't be matched up with the trait's parameters.
+fn suite_args(
+    params: &[SuiteParam],
+    trait_: &Path,
+    implementer: &Type,
+) -> Option<(TokenStream, HashMap<String, TokenStream>)> {
+    let given: Vec<TokenStream> = match &trait_.segments.last()?.arguments {
+        PathArguments::None => Vec::new(),
+        PathArguments::AngleBracketed(args) => (args.args.iter())
+            .filter(|arg| !matches!(arg, GenericArgument::Lifetime(_)))
+            .map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty.to_token_stream()),
+                GenericArgument::Const(expr) => Some(quote! { { #expr } }),
+                _ => None,
+            })
+            .collect::<Option<_>>()?,
+        PathArguments::Parenthesized(_) => return None,
+    };
+    if given.len() > params.len() {
+        return None;
+    }
+    // Defaults may refer to `Self` and preceding parameters
+    let mut substitutions = HashMap::new();
+    substitutions.insert(String::from("Self"), implementer.to_token_stream());
+    let mut args = Vec::with_capacity(params.len());
+    for (i, param) in params.iter().enumerate() {
+        let (ident, default) = match param {
+            SuiteParam::Type { ident, default } => (ident, default.to_token_stream()),
+            SuiteParam::Const { ident, default, .. } => (
+                ident,
+                default
+                    .as_ref()
+                    .map(|default| quote! { { #default } })
+                    .to_token_stream(),
+            ),
+        };
+        let arg = match given.get(i) {
+            Some(arg) => arg.clone(),
+            None if default.is_empty() => return None,
+            None => substitute(default, &substitutions),
+        };
+        args.push(match param {
+            SuiteParam::Type { .. } => quote! { ::core::marker::PhantomData<#arg> },
+            SuiteParam::Const { wrapper, .. } => quote! { #wrapper<#arg> },
+        });
+        substitutions.insert(ident.to_string(), arg);
+    }
+    Some((quote! { (#(#args,)*) }, substitutions))
+}
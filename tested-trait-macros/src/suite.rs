@@ -0,0 +1,670 @@
+//! The interface between [`tested_trait`] and [`test_impl`].
+//!
+//! `#[tested_trait]` emits a hidden `macro_rules!` macro that is re-exported under the trait's
+//! name and forwards a description of the trait's associated tests to [`instantiate_suite`].
+//! `#[test_impl]` invokes that macro through the trait's path, passing an [`Instantiation`]
+//! describing the concrete implementation to test.
+
+use quote::{quote, ToTokens};
+use syn::{
+    braced,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Attribute, Expr, GenericArgument, Ident, LitInt, LitStr, Meta, Path, Token, Type,
+};
+
+use crate::test_impl::ConcreteImpl;
+
+mod kw {
+    syn::custom_keyword!(by_path);
+    syn::custom_keyword!(id);
+    syn::custom_keyword!(include);
+    syn::custom_keyword!(macro_rules);
+}
+
+/// `#[disabled("..")]? #[parallel]? #[out_of_line]? #[remote(..)]? #[params(..)]? #[types(..)]?
+/// #[accessor(id = 123)]? #[include(..)]* trait Foo { fn test1; fn test2; }`
+pub struct Suite {
+    /// Why the suite's tests aren't compiled, if the feature that `#[tested_trait(feature = ..)]`
+    /// gates them behind is disabled, in which case the suite has no tests
+    pub disabled: Option<LitStr>,
+    /// Whether the tests are run in parallel when in an integration test
+    pub parallel: bool,
+    /// Whether the suite holds the out-of-line tests of a `#[trait_tests]` module, which are
+    /// reported as tests of the trait they're for
+    pub out_of_line: bool,
+    /// `#[remote(path::to::Foreign<T>)]`
+    ///
+    /// The foreign trait that the suite's remote definition holds tests for, whose generic
+    /// arguments may refer to the definition's parameters
+    pub remote: Option<Path>,
+    /// `#[params(type T = Default, const N: ::path::to::ConstU8)]`
+    ///
+    /// The trait's generic parameters, which the implementation's generic arguments are
+    /// matched up with to reach the tests through the trait's handle and to identify the
+    /// implementation to its `tested_trait_suite()` accessor.
+    pub params: Vec<SuiteParam>,
+    /// `#[types(U = [u8, String])]`
+    ///
+    /// The default arguments that generic tests are instantiated with
+    pub types: Vec<TypeList>,
+    /// Identifies the trait for its `tested_trait_suite()` accessor, which finds the tests
+    /// instantiated for an implementation through
+    /// `tested_trait::__private::SuiteOf<ID, Args, _>`
+    pub accessor_id: Option<LitInt>,
+    /// `#[include(Bar<T>, { trait Bar { .. } })]`
+    ///
+    /// The suites of the supertraits included with `include_supertraits` and of the
+    /// `#[trait_tests]` modules included with `tests = ..`, which are instantiated alongside
+    /// the trait's own tests
+    pub included: Vec<Included>,
+    pub trait_name: Ident,
+    pub tests: Vec<SuiteTest>,
+}
+
+/// A suite included in another's: a supertrait's, or that of a `#[trait_tests]` module.
+pub struct Included {
+    /// The included trait as written where it was included, whose generic arguments may refer
+    /// to the including trait's parameters
+    pub path: Path,
+    /// Whether `path` was written in `#[test_impl]`, so that the included suite's handle can
+    /// be reached through it rather than through its accessor on the including trait
+    pub by_path: bool,
+    pub suite: Suite,
+}
+
+/// How a generic type or const parameter of the trait is represented in the arguments passed
+/// to its handle and in `SuiteOf`'s `Args`.
+/// Lifetime parameters are omitted, as they don't distinguish concrete implementations.
+pub enum SuiteParam {
+    Type {
+        ident: Ident,
+        default: Option<Type>,
+    },
+    /// A const parameter, wrapped in one of `tested_trait::__private`'s `Const*` types
+    Const {
+        ident: Ident,
+        wrapper: Path,
+        default: Option<Expr>,
+    },
+}
+
+/// `#[kind(ShouldPanic)]? #[optional("Self: Default", ..)]? #[ignore = "reason"]?
+/// #[timeout(500)]? #[serial]? #[should_panic = "message"]? #[fixture]? #[generics(..)]?
+/// fn test;`
+pub struct SuiteTest {
+    pub ident: Ident,
+    /// The variant of `tested_trait::TestKind` describing the test, if not `Standard`
+    pub kind: Option<Ident>,
+    /// The bounds of an optional test, which is skipped if they aren't satisfied
+    pub optional_bounds: Option<Vec<LitStr>>,
+    /// Whether the test is ignored, and if so, why
+    pub ignore: Marker,
+    /// Milliseconds after which the test fails if it hasn't finished
+    pub timeout_ms: Option<LitInt>,
+    /// Whether the test must not run concurrently with other associated tests
+    pub serial: bool,
+    /// Whether the test should panic, and if so, with what message, when this is checked by
+    /// the test harness rather than by the test itself (i.e. without the `std` feature)
+    pub should_panic: Marker,
+    /// Whether the test takes `self`, which is run against each of the instantiation's
+    /// fixtures
+    pub fixture: bool,
+    /// `#[generics(type U, const N: ::path::to::ConstU8)]`
+    ///
+    /// The test's generic parameters, which it's instantiated over the type lists of
+    pub generics: Vec<SuiteParam>,
+}
+
+impl Parse for Suite {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut disabled = None;
+        let mut parallel = false;
+        let mut out_of_line = false;
+        let mut remote = None;
+        let mut params = Vec::new();
+        let mut types = Vec::new();
+        let mut accessor_id = None;
+        let mut included = Vec::new();
+        for attr in input.call(Attribute::parse_outer)? {
+            if attr.path().is_ident("disabled") {
+                disabled = Some(attr.parse_args()?);
+            } else if attr.path().is_ident("parallel") {
+                attr.meta.require_path_only()?;
+                parallel = true;
+            } else if attr.path().is_ident("out_of_line") {
+                attr.meta.require_path_only()?;
+                out_of_line = true;
+            } else if attr.path().is_ident("remote") {
+                remote = Some(attr.parse_args()?);
+            } else if attr.path().is_ident("params") {
+                let parsed =
+                    attr.parse_args_with(Punctuated::<SuiteParam, Token![,]>::parse_terminated)?;
+                params = parsed.into_iter().collect();
+            } else if attr.path().is_ident("types") {
+                types = parse_type_lists(&attr)?;
+            } else if attr.path().is_ident("accessor") {
+                accessor_id = Some(attr.parse_args_with(|input: ParseStream| {
+                    input.parse::<kw::id>()?;
+                    input.parse::<Token![=]>()?;
+                    input.parse()
+                })?);
+            } else if attr.path().is_ident("include") {
+                included.push(attr.parse_args()?);
+            } else {
+                return Err(syn::Error::new_spanned(attr, "unexpected attribute"));
+            }
+        }
+        input.parse::<Token![trait]>()?;
+        let trait_name = input.parse()?;
+        let content;
+        braced!(content in input);
+        let mut tests = Vec::new();
+        while !content.is_empty() {
+            tests.push(content.parse()?);
+        }
+        Ok(Self {
+            disabled,
+            parallel,
+            out_of_line,
+            remote,
+            params,
+            types,
+            accessor_id,
+            included,
+            trait_name,
+            tests,
+        })
+    }
+}
+
+impl Parse for SuiteParam {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![type]) {
+            input.parse::<Token![type]>()?;
+            let ident = input.parse()?;
+            let default = match input.parse::<Option<Token![=]>>()? {
+                Some(_) => Some(input.parse()?),
+                None => None,
+            };
+            Ok(Self::Type { ident, default })
+        } else {
+            input.parse::<Token![const]>()?;
+            let ident = input.parse()?;
+            input.parse::<Token![:]>()?;
+            let wrapper = input.parse()?;
+            let default = match input.parse::<Option<Token![=]>>()? {
+                Some(_) => Some(input.parse()?),
+                None => None,
+            };
+            Ok(Self::Const {
+                ident,
+                wrapper,
+                default,
+            })
+        }
+    }
+}
+
+impl ToTokens for Suite {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let Self {
+            disabled,
+            parallel,
+            out_of_line,
+            remote,
+            params,
+            types,
+            accessor_id,
+            included,
+            trait_name,
+            tests,
+        } = self;
+        if let Some(message) = disabled {
+            tokens.extend(quote! { #[disabled(#message)] });
+        }
+        if *parallel {
+            tokens.extend(quote! { #[parallel] });
+        }
+        if *out_of_line {
+            tokens.extend(quote! { #[out_of_line] });
+        }
+        if let Some(remote) = remote {
+            tokens.extend(quote! { #[remote(#remote)] });
+        }
+        if !params.is_empty() {
+            tokens.extend(quote! { #[params(#(#params),*)] });
+        }
+        if !types.is_empty() {
+            tokens.extend(quote! { #[types(#(#types),*)] });
+        }
+        if let Some(id) = accessor_id {
+            tokens.extend(quote! { #[accessor(id = #id)] });
+        }
+        for included in included {
+            tokens.extend(quote! { #[include(#included)] });
+        }
+        tokens.extend(quote! { trait #trait_name { #(#tests)* } });
+    }
+}
+
+/// `Bar<T>, { trait Bar { .. } }` or `path::to::extra, { trait extra { .. } }, by_path`
+impl Parse for Included {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let suite;
+        braced!(suite in input);
+        let suite = suite.parse()?;
+        let by_path = match input.parse::<Option<Token![,]>>()? {
+            Some(_) => {
+                input.parse::<kw::by_path>()?;
+                true
+            }
+            None => false,
+        };
+        Ok(Self {
+            path,
+            by_path,
+            suite,
+        })
+    }
+}
+
+impl ToTokens for Included {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let Self {
+            path,
+            by_path,
+            suite,
+        } = self;
+        tokens.extend(quote! { #path, { #suite } });
+        if *by_path {
+            tokens.extend(quote! { , by_path });
+        }
+    }
+}
+
+/// `#[attr]` or `#[attr = "message"]`, such as `#[ignore = "reason"]`, if present
+#[derive(Default)]
+pub enum Marker {
+    #[default]
+    Absent,
+    Present,
+    WithMessage(LitStr),
+}
+
+impl Marker {
+    pub fn is_present(&self) -> bool {
+        !matches!(self, Self::Absent)
+    }
+
+    pub fn message(&self) -> Option<&LitStr> {
+        match self {
+            Self::WithMessage(message) => Some(message),
+            Self::Absent | Self::Present => None,
+        }
+    }
+
+    /// Parses `#[attr]` or `#[attr = "message"]`.
+    fn parse(meta: Meta) -> syn::Result<Self> {
+        match meta {
+            Meta::NameValue(meta) => Ok(Self::WithMessage(syn::parse2(
+                meta.value.into_token_stream(),
+            )?)),
+            meta => {
+                meta.require_path_only()?;
+                Ok(Self::Present)
+            }
+        }
+    }
+
+    /// Generates `#[name]` or `#[name = "message"]`, if present.
+    pub fn to_attr(&self, name: &str) -> proc_macro2::TokenStream {
+        let name = Ident::new(name, proc_macro2::Span::call_site());
+        match self {
+            Self::Absent => quote! {},
+            Self::Present => quote! { #[#name] },
+            Self::WithMessage(message) => quote! { #[#name = #message] },
+        }
+    }
+}
+
+impl Parse for SuiteTest {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut kind = None;
+        let mut optional_bounds = None;
+        let mut ignore = Marker::Absent;
+        let mut timeout_ms = None;
+        let mut serial = false;
+        let mut should_panic = Marker::Absent;
+        let mut fixture = false;
+        let mut generics = Vec::new();
+        for attr in input.call(Attribute::parse_outer)? {
+            if attr.path().is_ident("kind") {
+                kind = Some(attr.parse_args()?);
+            } else if attr.path().is_ident("optional") {
+                let bounds =
+                    attr.parse_args_with(Punctuated::<LitStr, Token![,]>::parse_terminated)?;
+                optional_bounds = Some(bounds.into_iter().collect());
+            } else if attr.path().is_ident("ignore") {
+                ignore = Marker::parse(attr.meta)?;
+            } else if attr.path().is_ident("should_panic") {
+                should_panic = Marker::parse(attr.meta)?;
+            } else if attr.path().is_ident("timeout") {
+                timeout_ms = Some(attr.parse_args()?);
+            } else if attr.path().is_ident("serial") {
+                attr.meta.require_path_only()?;
+                serial = true;
+            } else if attr.path().is_ident("fixture") {
+                attr.meta.require_path_only()?;
+                fixture = true;
+            } else if attr.path().is_ident("generics") {
+                let parsed =
+                    attr.parse_args_with(Punctuated::<SuiteParam, Token![,]>::parse_terminated)?;
+                generics = parsed.into_iter().collect();
+            } else {
+                return Err(syn::Error::new_spanned(attr, "unexpected attribute"));
+            }
+        }
+        input.parse::<Token![fn]>()?;
+        let ident = input.parse()?;
+        input.parse::<Token![;]>()?;
+        Ok(Self {
+            ident,
+            kind,
+            optional_bounds,
+            ignore,
+            timeout_ms,
+            serial,
+            should_panic,
+            fixture,
+            generics,
+        })
+    }
+}
+
+impl ToTokens for SuiteParam {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self {
+            Self::Type { ident, default } => {
+                let default = default.as_ref().map(|default| quote! { = #default });
+                tokens.extend(quote! { type #ident #default });
+            }
+            Self::Const {
+                ident,
+                wrapper,
+                default,
+            } => {
+                let default = default.as_ref().map(|default| quote! { = #default });
+                tokens.extend(quote! { const #ident: #wrapper #default });
+            }
+        }
+    }
+}
+
+/// `U = [u8, String]`: the arguments a generic test's parameter is instantiated with
+#[derive(Clone)]
+pub struct TypeList {
+    pub param: Ident,
+    pub args: Punctuated<GenericArgument, Token![,]>,
+}
+
+impl Parse for TypeList {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let param = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let content;
+        syn::bracketed!(content in input);
+        Ok(Self {
+            param,
+            args: content.parse_terminated(GenericArgument::parse, Token![,])?,
+        })
+    }
+}
+
+impl ToTokens for TypeList {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let Self { param, args } = self;
+        tokens.extend(quote! { #param = [#args] });
+    }
+}
+
+/// Parses `#[types(U = [u8, String], N = [1, 2])]`.
+pub fn parse_type_lists(attr: &Attribute) -> syn::Result<Vec<TypeList>> {
+    let lists = attr.parse_args_with(Punctuated::<TypeList, Token![,]>::parse_terminated)?;
+    Ok(lists.into_iter().collect())
+}
+
+impl ToTokens for SuiteTest {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let Self {
+            ident,
+            kind,
+            optional_bounds,
+            ignore,
+            timeout_ms,
+            serial,
+            should_panic,
+            fixture,
+            generics,
+        } = self;
+        if let Some(kind) = kind {
+            tokens.extend(quote! { #[kind(#kind)] });
+        }
+        if let Some(bounds) = optional_bounds {
+            tokens.extend(quote! { #[optional(#(#bounds),*)] });
+        }
+        tokens.extend(ignore.to_attr("ignore"));
+        if let Some(ms) = timeout_ms {
+            tokens.extend(quote! { #[timeout(#ms)] });
+        }
+        if *serial {
+            tokens.extend(quote! { #[serial] });
+        }
+        tokens.extend(should_panic.to_attr("should_panic"));
+        if *fixture {
+            tokens.extend(quote! { #[fixture] });
+        }
+        if !generics.is_empty() {
+            tokens.extend(quote! { #[generics(#(#generics),*)] });
+        }
+        tokens.extend(quote! { fn #ident; });
+    }
+}
+
+/// `#[in_integration_test]? #[fixture(expr)]* #[fixture::name(expr)]* #[types(..)]?
+/// #[tests(..)]? #[subject({ Suite })]? #[with(..)]? #[checked(..)]? mod name { Type: Trait }`
+pub struct Instantiation {
+    pub in_integration_test: bool,
+    /// The values that tests taking `self` are run against
+    pub fixtures: Vec<Fixture>,
+    /// The arguments generic tests are instantiated with, overriding the trait's
+    pub types: Vec<TypeList>,
+    /// `#[tests(extra::extra, path::to::more::more)]`
+    ///
+    /// The traits of the `#[trait_tests]` modules whose suites are yet to be included in the
+    /// trait's, whose macros are invoked in turn (the first of which is currently being
+    /// expanded)
+    pub tests: Vec<Path>,
+    /// The trait's suite, once its macro has passed the instantiation on to include the
+    /// `tests`
+    pub subject: Option<Box<Suite>>,
+    /// `#[with(path::to::ForeignTests)]`
+    ///
+    /// The remote definition holding the tests of the (foreign) trait, which the suite was
+    /// found through instead of the trait
+    pub with: Option<Path>,
+    /// `#[checked({ .. })]`
+    ///
+    /// Checks that the implementation instantiates the annotated generic impl, evaluating to a
+    /// `PhantomData<*const Type>` that tests reach the implementer's handle through, so that
+    /// they're left uninferred without further errors along with a failing check
+    pub checked: Option<Box<Expr>>,
+    pub name: Ident,
+    pub concrete: ConcreteImpl,
+}
+
+/// A value of the implementing type that tests taking `self` are run against, which is
+/// evaluated anew for each test.
+#[derive(Clone)]
+pub struct Fixture {
+    /// Distinguishes the tests run against the fixture, if the implementation has several
+    pub name: Option<Ident>,
+    pub expr: Expr,
+}
+
+impl Parse for Instantiation {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut in_integration_test = false;
+        let mut fixtures = Vec::new();
+        let mut types = Vec::new();
+        let mut tests = Vec::new();
+        let mut subject = None;
+        let mut with = None;
+        let mut checked = None;
+        for attr in input.call(Attribute::parse_outer)? {
+            let segments = attr.path().segments.iter().collect::<Vec<_>>();
+            match &segments[..] {
+                [segment] if segment.ident == "in_integration_test" => {
+                    attr.meta.require_path_only()?;
+                    in_integration_test = true;
+                }
+                [segment] if segment.ident == "fixture" => fixtures.push(Fixture {
+                    name: None,
+                    expr: attr.parse_args()?,
+                }),
+                [segment, name] if segment.ident == "fixture" => fixtures.push(Fixture {
+                    name: Some(name.ident.clone()),
+                    expr: attr.parse_args()?,
+                }),
+                [segment] if segment.ident == "types" => types = parse_type_lists(&attr)?,
+                [segment] if segment.ident == "tests" => {
+                    let parsed =
+                        attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?;
+                    tests = parsed.into_iter().collect();
+                }
+                [segment] if segment.ident == "subject" => {
+                    subject = Some(Box::new(attr.parse_args_with(|input: ParseStream| {
+                        let suite;
+                        braced!(suite in input);
+                        suite.parse()
+                    })?));
+                }
+                [segment] if segment.ident == "with" => with = Some(attr.parse_args()?),
+                [segment] if segment.ident == "checked" => {
+                    checked = Some(Box::new(attr.parse_args()?));
+                }
+                _ => return Err(syn::Error::new_spanned(attr, "unexpected attribute")),
+            }
+        }
+        input.parse::<Token![mod]>()?;
+        let name = input.parse()?;
+        let content;
+        braced!(content in input);
+        let concrete = content.parse()?;
+        Ok(Self {
+            in_integration_test,
+            fixtures,
+            types,
+            tests,
+            subject,
+            with,
+            checked,
+            name,
+            concrete,
+        })
+    }
+}
+
+impl ToTokens for Instantiation {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let Self {
+            in_integration_test,
+            fixtures,
+            types,
+            tests,
+            subject,
+            with,
+            checked,
+            name,
+            concrete,
+        } = self;
+        if *in_integration_test {
+            tokens.extend(quote! { #[in_integration_test] });
+        }
+        for Fixture { name, expr } in fixtures {
+            match name {
+                Some(name) => tokens.extend(quote! { #[fixture::#name(#expr)] }),
+                None => tokens.extend(quote! { #[fixture(#expr)] }),
+            }
+        }
+        if !types.is_empty() {
+            tokens.extend(quote! { #[types(#(#types),*)] });
+        }
+        if !tests.is_empty() {
+            tokens.extend(quote! { #[tests(#(#tests),*)] });
+        }
+        if let Some(subject) = subject {
+            tokens.extend(quote! { #[subject({ #subject })] });
+        }
+        if let Some(with) = with {
+            tokens.extend(quote! { #[with(#with)] });
+        }
+        if let Some(checked) = checked {
+            tokens.extend(quote! { #[checked(#checked)] });
+        }
+        tokens.extend(quote! { mod #name { #concrete } });
+    }
+}
+
+/// `include #[macro_export]? macro_rules! name { Suite } Bar<T>, self::extra::extra`
+///
+/// A request to include the suites of supertraits and `#[trait_tests]` modules in `subtrait`,
+/// which is passed to their macros in turn (the first of which is currently being expanded)
+/// so that each is found through the path naming it in the subtrait's definition. Once all
+/// are included, the subtrait's suite macro is defined with the given attributes.
+pub struct Inclusion {
+    pub attrs: Vec<Attribute>,
+    pub macro_name: Ident,
+    pub subtrait: Suite,
+    pub included: Punctuated<Path, Token![,]>,
+}
+
+impl Parse for Inclusion {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<kw::include>()?;
+        let attrs = input.call(Attribute::parse_outer)?;
+        input.parse::<kw::macro_rules>()?;
+        input.parse::<Token![!]>()?;
+        let macro_name = input.parse()?;
+        let subtrait;
+        braced!(subtrait in input);
+        Ok(Self {
+            attrs,
+            macro_name,
+            subtrait: subtrait.parse()?,
+            included: Punctuated::parse_separated_nonempty(input)?,
+        })
+    }
+}
+
+impl ToTokens for Inclusion {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let Self {
+            attrs,
+            macro_name,
+            subtrait,
+            included,
+        } = self;
+        tokens.extend(quote! {
+            include #(#attrs)* macro_rules! #macro_name { #subtrait } #included
+        });
+    }
+}
+
+/// Whether the suite macro was invoked with an [`Inclusion`] rather than an
+/// [`Instantiation`].
+pub fn peek_inclusion(input: ParseStream) -> bool {
+    input.peek(kw::include)
+}
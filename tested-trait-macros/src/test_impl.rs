@@ -0,0 +1,1165 @@
+use std::collections::{HashMap, HashSet};
+
+use manyhow::{bail, error_message, ResultExt};
+use proc_macro2::{Delimiter, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream, Parser},
+    parse_quote,
+    punctuated::Punctuated,
+    token::{Colon, Paren},
+    Attribute, ConstParam, Expr, GenericArgument, GenericParam, Generics, Ident, ItemImpl, LitStr,
+    Path, PathArguments, PathSegment, Token, TraitBoundModifier, Type, TypeParamBound,
+    WherePredicate,
+};
+
+use super::{
+    substitute,
+    suite::{Fixture, Instantiation, TypeList},
+};
+
+pub fn test_impl(args: TokenStream, item: TokenStream) -> manyhow::Result<TokenStream> {
+    let ast = parse(args, item)?;
+    let model = analyze(ast)?;
+    let ir = lower(model);
+    Ok(codegen(ir))
+}
+
+/// Tests the implementations listed in `test_impls!`, which takes the same arguments as
+/// `#[test_impl]` without the impl itself.
+pub fn test_impls(input: TokenStream) -> manyhow::Result<TokenStream> {
+    let ast = parse_standalone(input)?;
+    let model = analyze(ast)?;
+    let ir = lower(model);
+    Ok(codegen(ir))
+}
+
+const MACRO: &str = "test_impl";
+
+struct Ast {
+    /// The annotated impl, or `None` for `test_impls!`
+    trait_impl: Option<ItemImpl>,
+    /// `test_impls!`'s attributes, e.g. `#[in_integration_test]`
+    attrs: Vec<Attribute>,
+    args: Punctuated<Arg, Token![,]>,
+}
+
+mod kw {
+    syn::custom_keyword!(name);
+    syn::custom_keyword!(fixture);
+    syn::custom_keyword!(fixtures);
+    syn::custom_keyword!(tests);
+    syn::custom_keyword!(types);
+    syn::custom_keyword!(with);
+}
+
+enum Arg {
+    /// `name = "module_name"`
+    Name(LitStr),
+    /// `fixture = Type::new()`
+    Fixture(kw::fixture, Box<Expr>),
+    /// `fixtures(empty = Type::new(), full = Type::full())`
+    Fixtures(kw::fixtures, Punctuated<NamedFixture, Token![,]>),
+    /// `types(U = [u8, String])`
+    Types(Punctuated<TypeList, Token![,]>),
+    /// `tests(extra, path::to::more)`
+    Tests(Punctuated<Path, Token![,]>),
+    /// `with = ForeignTests`
+    With(kw::with, Path),
+    /// `T in [u32, String]`
+    Values(ParamValues),
+    /// `Type: Trait`
+    Concrete(Box<ConcreteImpl>),
+}
+
+/// `T in [u32, String]` or `const N in [1, 2]`: the values a generic parameter of the annotated
+/// impl is instantiated with
+struct ParamValues {
+    const_: Option<Token![const]>,
+    param: Ident,
+    args: Punctuated<GenericArgument, Token![,]>,
+}
+
+/// `empty = Type::new()`
+struct NamedFixture {
+    name: Ident,
+    expr: Expr,
+}
+
+impl Parse for Arg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(kw::name) && input.peek2(Token![=]) {
+            input.parse::<kw::name>()?;
+            input.parse::<Token![=]>()?;
+            Ok(Self::Name(input.parse()?))
+        } else if input.peek(kw::fixture) && input.peek2(Token![=]) {
+            let kw = input.parse()?;
+            input.parse::<Token![=]>()?;
+            Ok(Self::Fixture(kw, input.parse()?))
+        } else if input.peek(kw::fixtures) && input.peek2(Paren) {
+            let kw = input.parse()?;
+            let content;
+            syn::parenthesized!(content in input);
+            Ok(Self::Fixtures(
+                kw,
+                content.parse_terminated(NamedFixture::parse, Token![,])?,
+            ))
+        } else if input.peek(kw::types) && input.peek2(Paren) {
+            input.parse::<kw::types>()?;
+            let content;
+            syn::parenthesized!(content in input);
+            Ok(Self::Types(
+                content.parse_terminated(TypeList::parse, Token![,])?,
+            ))
+        } else if input.peek(kw::with) && input.peek2(Token![=]) {
+            let kw = input.parse()?;
+            input.parse::<Token![=]>()?;
+            Ok(Self::With(kw, input.parse()?))
+        } else if input.peek(kw::tests) && input.peek2(Paren) {
+            input.parse::<kw::tests>()?;
+            let content;
+            syn::parenthesized!(content in input);
+            Ok(Self::Tests(
+                content.parse_terminated(Path::parse, Token![,])?,
+            ))
+        } else if input.peek(Token![const]) || (input.peek(Ident) && input.peek2(Token![in])) {
+            input.parse().map(Self::Values)
+        } else {
+            input.parse().map(Box::new).map(Self::Concrete)
+        }
+    }
+}
+
+impl Parse for ParamValues {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let const_ = input.parse()?;
+        let param = input.parse()?;
+        input.parse::<Token![in]>()?;
+        let content;
+        syn::bracketed!(content in input);
+        Ok(Self {
+            const_,
+            param,
+            args: content.parse_terminated(GenericArgument::parse, Token![,])?,
+        })
+    }
+}
+
+impl Parse for NamedFixture {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        Ok(Self {
+            name,
+            expr: input.parse()?,
+        })
+    }
+}
+
+pub struct ConcreteImpl {
+    pub implementer: Type,
+    pub colon: Colon,
+    pub trait_: Path,
+}
+
+impl Parse for ConcreteImpl {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            implementer: input.parse()?,
+            colon: input.parse()?,
+            trait_: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for ConcreteImpl {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Self {
+            implementer,
+            colon,
+            trait_,
+        } = self;
+        implementer.to_tokens(tokens);
+        colon.to_tokens(tokens);
+        trait_.to_tokens(tokens);
+    }
+}
+
+fn parse(args: TokenStream, item: TokenStream) -> manyhow::Result<Ast> {
+    let trait_impl = syn::parse2(item).map_err(|err| {
+        error_message!(
+            err.span(),
+            "#[{MACRO}] can only be used to annotate trait implementations"
+        )
+    })?;
+    let args = Punctuated::parse_terminated
+        .parse2(args)
+        .map_err(|err| error_message!(err.span(), "#[{MACRO}] received invalid arguments"))?;
+    Ok(Ast {
+        trait_impl: Some(trait_impl),
+        attrs: Vec::new(),
+        args,
+    })
+}
+
+fn parse_standalone(input: TokenStream) -> manyhow::Result<Ast> {
+    let (attrs, args) = (|input: ParseStream| {
+        Ok((
+            input.call(Attribute::parse_outer)?,
+            Punctuated::parse_terminated(input)?,
+        ))
+    })
+    .parse2(input)
+    .map_err(|err| error_message!(err.span(), "test_impls! received invalid arguments"))?;
+    Ok(Ast {
+        trait_impl: None,
+        attrs,
+        args,
+    })
+}
+
+struct Model {
+    /// The annotated impl, which is emitted unchanged
+    trait_impl: Option<ItemImpl>,
+    concrete_impls: Punctuated<ConcreteImpl, Token![,]>,
+    /// Overrides the name of the generated test module
+    name: Option<Ident>,
+    /// The names of the concrete implementations expanded from values of the impl's generic
+    /// parameters, if they were
+    names: Vec<Ident>,
+    /// The values that tests taking `self` are run against
+    fixtures: Vec<Fixture>,
+    /// The arguments generic tests are instantiated with, overriding the trait's
+    types: Vec<TypeList>,
+    /// The `#[trait_tests]` modules whose tests are run alongside the trait's
+    tests_modules: Vec<Path>,
+    /// The remote definition holding the trait's tests, if the trait is foreign
+    with: Option<Path>,
+    in_integration_test: bool,
+}
+
+fn analyze(ast: Ast) -> manyhow::Result<Model> {
+    let Ast {
+        trait_impl,
+        attrs,
+        args,
+    } = ast;
+    let mut name = None;
+    let mut fixtures: Vec<Fixture> = Vec::new();
+    let mut types: Vec<TypeList> = Vec::new();
+    let mut tests_modules: Vec<Path> = Vec::new();
+    let mut with = None;
+    let mut concrete_impls = Punctuated::new();
+    let mut matrix = Vec::new();
+    for arg in args {
+        match arg {
+            Arg::Name(lit) => {
+                if name.is_some() {
+                    bail!(lit, "#[{MACRO}(name = ..)] can only be specified once")
+                }
+                let ident = lit.parse::<Ident>().map_err(|_| {
+                    error_message!(lit, "#[{MACRO}(name = ..)] must be a valid identifier")
+                })?;
+                name = Some((lit, ident));
+            }
+            Arg::Fixture(kw, expr) => {
+                if !fixtures.is_empty() {
+                    bail!(
+                        kw,
+                        "#[{MACRO}(fixture = ..)] can only be specified once, use \
+                         #[{MACRO}(fixtures(name = .., ..))] for several fixtures"
+                    )
+                }
+                fixtures.push(Fixture {
+                    name: None,
+                    expr: *expr,
+                });
+            }
+            Arg::Fixtures(kw, named) => add_named_fixtures(&mut fixtures, kw, named)?,
+            Arg::Types(lists) => {
+                for list in lists {
+                    if types.iter().any(|listed| listed.param == list.param) {
+                        bail!(
+                            list.param,
+                            "the types of `{}` are listed multiple times",
+                            list.param
+                        )
+                    }
+                    types.push(list);
+                }
+            }
+            Arg::Tests(modules) => {
+                for module in modules {
+                    include_tests_module(&mut tests_modules, module)?;
+                }
+            }
+            Arg::With(kw, path) => {
+                if with.is_some() {
+                    bail!(kw, "#[{MACRO}(with = ..)] can only be specified once")
+                }
+                with = Some(path);
+            }
+            Arg::Values(values) => matrix.push(values),
+            Arg::Concrete(concrete) => concrete_impls.push(*concrete),
+        }
+    }
+    let mut names = Vec::new();
+    let (trait_impl, in_integration_test) = if let Some(trait_impl) = trait_impl {
+        analyze_impl(trait_impl, &mut concrete_impls, matrix, &mut names)?
+    } else {
+        let in_integration_test = analyze_standalone(attrs, &concrete_impls, &matrix)?;
+        (None, in_integration_test)
+    };
+
+    let name = match name {
+        Some((lit, _)) if concrete_impls.len() > 1 => bail!(
+            lit,
+            "#[{MACRO}(name = ..)] can only be used with a single concrete implementation"
+        ),
+        name => name.map(|(_, ident)| ident),
+    };
+
+    Ok(Model {
+        trait_impl,
+        concrete_impls,
+        name,
+        names,
+        fixtures,
+        types,
+        tests_modules,
+        with,
+        in_integration_test,
+    })
+}
+
+/// Adds the fixtures of `fixtures(name = .., ..)`, which can't be combined with an unnamed
+/// `fixture = ..`.
+fn add_named_fixtures(
+    fixtures: &mut Vec<Fixture>,
+    kw: kw::fixtures,
+    named: Punctuated<NamedFixture, Token![,]>,
+) -> manyhow::Result<()> {
+    if fixtures.iter().any(|fixture| fixture.name.is_none()) {
+        bail!(
+            kw,
+            "#[{MACRO}(fixtures(..))] cannot be combined with #[{MACRO}(fixture = ..)]"
+        )
+    }
+    for NamedFixture { name, expr } in named {
+        if fixtures
+            .iter()
+            .any(|fixture| fixture.name.as_ref() == Some(&name))
+        {
+            bail!(name, "the fixture `{name}` is defined multiple times")
+        }
+        fixtures.push(Fixture {
+            name: Some(name),
+            expr,
+        });
+    }
+    Ok(())
+}
+
+/// Adds a `#[trait_tests]` module to those whose tests are included, which are grouped by
+/// their module's name.
+fn include_tests_module(tests_modules: &mut Vec<Path>, module: Path) -> manyhow::Result<()> {
+    let name = |module: &Path| {
+        (module.segments.last())
+            .map(|segment| segment.ident.clone())
+            .expect("module `Path`s contain at least one segment")
+    };
+    if tests_modules
+        .iter()
+        .any(|listed| name(listed) == name(&module))
+    {
+        bail!(
+            module,
+            "the tests of a module named `{}` are already included",
+            name(&module)
+        )
+    }
+    tests_modules.push(module);
+    Ok(())
+}
+
+/// Checks the arguments and attributes of a `test_impls!` invocation, returning whether it's
+/// `#[in_integration_test]`.
+fn analyze_standalone(
+    attrs: Vec<Attribute>,
+    concrete_impls: &Punctuated<ConcreteImpl, Token![,]>,
+    matrix: &[ParamValues],
+) -> manyhow::Result<bool> {
+    if let Some(values) = matrix.first() {
+        bail!(
+            values.param,
+            "test_impls! doesn't support assigning values to generic parameters, as there's no \
+             generic impl to instantiate"
+        )
+    }
+    if concrete_impls.is_empty() {
+        bail!(
+            Span::call_site(),
+            "test_impls! requires specifying implementations to test, as in test_impls!(Type: Trait)"
+        )
+    }
+    let mut in_integration_test = false;
+    for attr in attrs {
+        if !attr.meta.path().is_ident("in_integration_test") {
+            bail!(attr, "test_impls! only supports #[in_integration_test]")
+        }
+        attr.meta.require_path_only()?;
+        in_integration_test = true;
+    }
+    Ok(in_integration_test)
+}
+
+/// Checks the annotated impl, filling in its concrete implementations if it isn't generic or
+/// they're assigned with values of its generic parameters, and removes its
+/// `#[in_integration_test]` attribute.
+fn analyze_impl(
+    mut trait_impl: ItemImpl,
+    concrete_impls: &mut Punctuated<ConcreteImpl, Token![,]>,
+    matrix: Vec<ParamValues>,
+    names: &mut Vec<Ident>,
+) -> manyhow::Result<(Option<ItemImpl>, bool)> {
+    let (negative_impl, trait_, _) = trait_impl.trait_.as_ref().ok_or_else(|| {
+        error_message!(
+            trait_impl,
+            "#[{MACRO}] can only be used to annotate trait implementations"
+        )
+    })?;
+    if let Some(negative_impl) = negative_impl {
+        bail!(
+            negative_impl,
+            "#[{MACRO}] does not support negative trait implementations"
+        )
+    }
+
+    let implementer = trait_impl.self_ty.as_ref().clone();
+    if trait_impl.generics.params.is_empty() {
+        if !concrete_impls.is_empty() {
+            bail!(
+                concrete_impls,
+                "#[{MACRO}] on a non-generic impl does not support specifying concrete implementations";
+            )
+        }
+        if let Some(values) = matrix.first() {
+            bail!(
+                values.param,
+                "#[{MACRO}] on a non-generic impl does not support assigning values to generic parameters"
+            )
+        }
+        concrete_impls.push(ConcreteImpl {
+            implementer,
+            colon: Colon::default(),
+            trait_: trait_.clone(),
+        });
+    } else if !matrix.is_empty() {
+        if !concrete_impls.is_empty() {
+            bail!(
+                concrete_impls,
+                "#[{MACRO}] does not support specifying concrete implementations alongside values of generic parameters"
+            )
+        }
+        for (concrete, name) in expand_matrix(&trait_impl, trait_, matrix)? {
+            concrete_impls.push(concrete);
+            names.push(name);
+        }
+    } else if concrete_impls.is_empty() {
+        return Err(error_message!(
+            concrete_impls,
+            "#[{MACRO}] on a generic impl requires specifying concrete implementations with #[{MACRO}({implementer}: {trait_})]",
+            implementer = implementer.to_token_stream(),
+            trait_ = trait_.to_token_stream(),
+        ))
+        .context(error_message!(
+            trait_impl.generics,
+            "associated tests for this generic implementation can only be instantiated for concrete types"
+        ));
+    } else {
+        // The rest of each concrete implementation is checked against the impl by `ImplCheck`
+        let implemented_trait = &trait_name(trait_).ident;
+        for concrete in concrete_impls.iter() {
+            if trait_name(&concrete.trait_).ident != *implemented_trait {
+                bail!(
+                    concrete.trait_,
+                    "`{}` isn't the trait implemented here, `{}`",
+                    super::pretty_print(&concrete.trait_),
+                    super::pretty_print(trait_);
+                    help = "concrete implementations must instantiate the annotated impl"
+                )
+            }
+        }
+    }
+
+    let in_integration_test = (trait_impl.attrs.iter())
+        .enumerate()
+        .find_map(|(idx, attr)| {
+            attr.meta
+                .path()
+                .is_ident("in_integration_test")
+                .then_some(idx)
+        })
+        .map(|idx| trait_impl.attrs.remove(idx))
+        .is_some();
+
+    Ok((Some(trait_impl), in_integration_test))
+}
+
+/// Expands values of the impl's generic parameters into the concrete implementations of their
+/// cartesian product, each named after its parameters' values, e.g. `Buffer_for_Ring_u8_16`.
+/// Lifetime parameters are instantiated with `'static`.
+fn expand_matrix(
+    trait_impl: &ItemImpl,
+    trait_: &Path,
+    matrix: Vec<ParamValues>,
+) -> manyhow::Result<Vec<(ConcreteImpl, Ident)>> {
+    let generics = &trait_impl.generics;
+    let assigned = check_matrix(generics, matrix)?;
+
+    // Name the implementations after the impl's trait and type, without its parameters
+    let erased = (assigned.iter())
+        .map(|values| (values.param.to_string(), TokenStream::new()))
+        .collect::<HashMap<_, _>>();
+    let self_ty = &trait_impl.self_ty;
+    let mut base = Vec::new();
+    let spelled_out = spell_out(substitute(trait_.to_token_stream(), &erased), &mut base) && {
+        base.push("for".to_owned());
+        spell_out(substitute(self_ty.to_token_stream(), &erased), &mut base)
+    };
+    if !spelled_out {
+        base = vec![
+            trait_name(trait_).ident.unraw().to_string(),
+            format!(
+                "{:016x}",
+                super::hash_tokens(quote! { #trait_ for #self_ty })
+            ),
+        ];
+    }
+
+    let lifetimes = (generics.lifetimes())
+        .map(|param| (param.lifetime.to_string(), quote! { 'static }))
+        .collect::<HashMap<_, _>>();
+    let mut instantiations = vec![(base, lifetimes)];
+    for values in &assigned {
+        instantiations = (instantiations.into_iter())
+            .flat_map(|(words, substitutions)| {
+                values.args.iter().map(move |arg| {
+                    let mut words = words.clone();
+                    if !spell_out(arg.to_token_stream(), &mut words) {
+                        words.push(format!("{:016x}", super::hash_tokens(arg)));
+                    }
+                    let mut substitutions = substitutions.clone();
+                    substitutions.insert(values.param.to_string(), arg.to_token_stream());
+                    (words, substitutions)
+                })
+            })
+            .collect();
+    }
+    (instantiations.into_iter())
+        .map(|(words, substitutions)| {
+            let concrete = ConcreteImpl {
+                implementer: syn::parse2(substitute(self_ty.to_token_stream(), &substitutions))?,
+                colon: Colon::default(),
+                trait_: syn::parse2(substitute(trait_.to_token_stream(), &substitutions))?,
+            };
+            Ok((concrete, Ident::new(&words.join("_"), Span::call_site())))
+        })
+        .collect()
+}
+
+/// Checks each of the impl's type and const parameters is assigned values exactly once.
+fn check_matrix(
+    generics: &Generics,
+    matrix: Vec<ParamValues>,
+) -> manyhow::Result<Vec<ParamValues>> {
+    let mut assigned: Vec<ParamValues> = Vec::new();
+    for values in matrix {
+        let is_const = if generics.type_params().any(|p| p.ident == values.param) {
+            false
+        } else if generics.const_params().any(|p| p.ident == values.param) {
+            true
+        } else {
+            bail!(
+                values.param,
+                "the annotated impl has no generic parameter named `{}`",
+                values.param
+            )
+        };
+        match (is_const, &values.const_) {
+            (true, None) => bail!(
+                values.param,
+                "`{0}` is a const parameter, so its values must be listed with `const {0} in [..]`",
+                values.param
+            ),
+            (false, Some(const_)) => bail!(
+                const_,
+                "`{0}` is a type parameter, so its values must be listed with `{0} in [..]`",
+                values.param
+            ),
+            _ => {}
+        }
+        if assigned.iter().any(|other| other.param == values.param) {
+            bail!(
+                values.param,
+                "`{}` is already assigned values",
+                values.param
+            )
+        }
+        if values.args.is_empty() {
+            bail!(
+                values.param,
+                "`{}` must be assigned at least one value",
+                values.param
+            )
+        }
+        assigned.push(values);
+    }
+    let unassigned = (generics.type_params().map(|p| (&p.ident, "")))
+        .chain(generics.const_params().map(|p| (&p.ident, "const ")))
+        .find(|(ident, _)| !assigned.iter().any(|values| values.param == **ident));
+    if let Some((ident, const_)) = unassigned {
+        bail!(
+            ident,
+            "`{ident}` must be assigned values with `{const_}{ident} in [..]`, as the impl's \
+             other generic parameters are"
+        )
+    }
+
+    Ok(assigned)
+}
+
+/// The last segment of a trait's path, naming the trait and holding its arguments
+fn trait_name(trait_: &Path) -> &PathSegment {
+    (trait_.segments)
+        .last()
+        .expect("trait `Path`s contain at least one segment")
+}
+
+struct Ir {
+    trait_impl: Option<ItemImpl>,
+    check: Option<ImplCheck>,
+    instantiations: Vec<Instantiation>,
+}
+
+/// Checks at compile time that the concrete implementations listed for a generic impl are
+/// instantiations of it, by implementing a marker trait for each type in the impl's header,
+/// tagged with its position, for the types before it, and resolving the marker for each of the corresponding types of a
+/// concrete implementation in turn.
+///
+/// The types are resolved by a chain of calls inferring the types before each one from the last
+/// one's output, so a mismatch is reported once, at the first type that differs, after which
+/// the rest of the chain is left ambiguous without further errors. The chain evaluates to the
+/// implementer, which the tests generated for the concrete implementation reach its handle
+/// through, so they're left uninferred along with it.
+struct ImplCheck {
+    /// The marker trait, named after a hash of the impl's header
+    marker: Ident,
+    /// The impl's header, e.g. `impl<T> Wrapper<T> for Option<T>`, for error messages
+    header: String,
+    /// The impl's generic parameters and `where` clause
+    generics: Generics,
+    self_ty: Type,
+    /// Const parameters passed as trait arguments, which are wrapped in types to be inferred
+    const_params: Vec<(Ident, ConstParam)>,
+    /// The types of the impl's header that its generic parameters are inferred from
+    patterns: Vec<TokenStream>,
+    /// Positions of the trait's arguments that generic parameters are inferred from, along
+    /// with the wrappers of const arguments
+    inferred: Vec<(usize, Option<Ident>)>,
+    /// The chain checking each concrete implementation
+    checked: Vec<Expr>,
+}
+
+impl ImplCheck {
+    fn new(trait_impl: &ItemImpl) -> Self {
+        let generics = trait_impl.generics.clone();
+        let const_param = |ident: Option<&Ident>| {
+            (generics.const_params()).find(|param| Some(&param.ident) == ident)
+        };
+
+        let (_, trait_, _) =
+            (trait_impl.trait_.as_ref()).expect("only trait implementations are tested");
+        let self_ty = &trait_impl.self_ty;
+        let params = &generics.params;
+        let header = format!(
+            "impl<{}> {} for {}",
+            super::pretty_print(params),
+            super::pretty_print(trait_),
+            super::pretty_print(self_ty)
+        );
+        let marker = format_ident!(
+            "__TestedTraitInstantiates_{:016x}",
+            super::hash_tokens(quote! { impl<#params> #trait_ for #self_ty })
+        );
+        let mut const_params = Vec::new();
+        let mut patterns = vec![quote! { *const #self_ty }];
+        let mut inferred = Vec::new();
+        for (idx, arg) in trait_args(trait_).enumerate() {
+            let const_ = match arg {
+                GenericArgument::Type(Type::Path(ty)) if ty.qself.is_none() => {
+                    const_param(ty.path.get_ident())
+                }
+                GenericArgument::Const(Expr::Path(expr)) if expr.qself.is_none() => {
+                    const_param(expr.path.get_ident())
+                }
+                _ => None,
+            };
+            match (arg, const_) {
+                (_, Some(param)) => {
+                    let wrapper = format_ident!("{marker}_{}", param.ident);
+                    let ident = &param.ident;
+                    patterns.push(quote! { #wrapper<#ident> });
+                    const_params.push((wrapper.clone(), param.clone()));
+                    inferred.push((idx, Some(wrapper)));
+                }
+                (GenericArgument::Type(ty), None) => {
+                    patterns.push(quote! { *const #ty });
+                    inferred.push((idx, None));
+                }
+                // Other arguments don't mention generic parameters
+                _ => {}
+            }
+        }
+
+        ImplCheck {
+            marker,
+            header,
+            generics,
+            self_ty: (*trait_impl.self_ty).clone(),
+            const_params,
+            patterns,
+            inferred,
+            checked: Vec::new(),
+        }
+    }
+
+    /// The chain checking `concrete`, which evaluates to a `PhantomData<*const Implementer>`
+    /// if it instantiates the impl, or `None` if it can't be checked.
+    fn check(&mut self, concrete: &ConcreteImpl) -> Option<Expr> {
+        let args = trait_args(&concrete.trait_).collect::<Vec<_>>();
+        let implementer = &concrete.implementer;
+        let mut types = vec![(
+            quote! { *const #implementer },
+            implementer.to_token_stream(),
+        )];
+        for (idx, wrapper) in &self.inferred {
+            // Omitted arguments are defaulted, which generic parameters can't be inferred from
+            let arg = args.get(*idx)?;
+            types.push(if let Some(wrapper) = wrapper {
+                (quote! { #wrapper<{ #arg }> }, arg.to_token_stream())
+            } else {
+                (quote! { *const #arg }, arg.to_token_stream())
+            });
+        }
+
+        let marker = &self.marker;
+        let steps = (1..=types.len()).map(|len| {
+            let (ty, arg) = &types[len - 1];
+            // Spans the step over the type that it checks, where a mismatch is
+            // reported: both the marker's bound and the argument it's inferred from are
+            // reported at the start of the step, and the spans covering them end at its end
+            let (first, last) = span_range(arg);
+            let close = quote_spanned! {last=> > };
+            let phantom_data = quote_spanned! {first=>
+                ::core::marker::PhantomData<*const _ #close
+            };
+            let step = quote_spanned! {first=>
+                <#phantom_data as #marker<[#ty; #len] #close
+            };
+            let call = quote_spanned! {last=> >::check(checked) };
+            quote! { let checked = #step #call; }
+        });
+        let checked = parse_quote! {{
+            let checked = ::core::marker::PhantomData::<*const ()>;
+            #(#steps)*
+            checked
+        }};
+        self.checked.push(Expr::clone(&checked));
+        Some(checked)
+    }
+}
+
+/// The spans of the first and last tokens of `tokens`, which spans covering them start and end
+/// at.
+fn span_range(tokens: &TokenStream) -> (Span, Span) {
+    let mut tokens = tokens.clone().into_iter();
+    let first = tokens
+        .next()
+        .map_or_else(Span::call_site, |token| token.span());
+    let last = tokens.last().map_or(first, |token| match token {
+        TokenTree::Group(group) => group.span_close(),
+        token => token.span(),
+    });
+    (first, last)
+}
+
+/// Whether the type parameter `ident` is bounded by `?Sized`.
+fn is_maybe_sized(generics: &Generics, ident: &Ident) -> bool {
+    let is_maybe = |bound: &TypeParamBound| matches!(bound, TypeParamBound::Trait(bound) if matches!(bound.modifier, TraitBoundModifier::Maybe(_)));
+    let inline = (generics.type_params())
+        .filter(|param| param.ident == *ident)
+        .flat_map(|param| &param.bounds);
+    let where_clause = (generics.where_clause.iter())
+        .flat_map(|where_clause| &where_clause.predicates)
+        .filter_map(|predicate| match predicate {
+            WherePredicate::Type(predicate) => Some(predicate),
+            _ => None,
+        })
+        .filter(|predicate| {
+            matches!(&predicate.bounded_ty, Type::Path(ty) if ty.qself.is_none() && ty.path.is_ident(ident))
+        })
+        .flat_map(|predicate| &predicate.bounds);
+    inline.chain(where_clause).any(is_maybe)
+}
+
+/// Whether `tokens` mention `ident`.
+fn mentions(tokens: TokenStream, ident: &Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(other) => other == *ident,
+        TokenTree::Group(group) => mentions(group.stream(), ident),
+        _ => false,
+    })
+}
+
+/// The arguments of a trait, excluding lifetimes, which don't distinguish implementations
+fn trait_args(trait_: &Path) -> impl Iterator<Item = &GenericArgument> {
+    let args = if let PathArguments::AngleBracketed(args) = &trait_name(trait_).arguments {
+        Some(&args.args)
+    } else {
+        None
+    };
+    (args.into_iter().flatten()).filter(|arg| !matches!(arg, GenericArgument::Lifetime(_)))
+}
+
+fn lower(model: Model) -> Ir {
+    let Model {
+        trait_impl,
+        concrete_impls,
+        name,
+        names,
+        fixtures,
+        types,
+        tests_modules,
+        with,
+        in_integration_test,
+    } = model;
+    let tests = (tests_modules.iter())
+        .map(super::out_of_line_trait)
+        .collect::<Vec<_>>();
+    let mut check = (trait_impl.as_ref())
+        .filter(|trait_impl| !trait_impl.generics.params.is_empty())
+        .map(ImplCheck::new);
+    let names = match name {
+        Some(name) => vec![name],
+        None if names.is_empty() => disambiguate(concrete_impls.iter().map(module_name).collect()),
+        None => disambiguate(names),
+    };
+    let instantiations = (concrete_impls.into_iter())
+        .zip(names)
+        .map(|(concrete, name)| Instantiation {
+            in_integration_test,
+            fixtures: fixtures.clone(),
+            types: types.clone(),
+            tests: tests.clone(),
+            subject: None,
+            with: with.clone(),
+            checked: (check.as_mut())
+                .and_then(|check| check.check(&concrete))
+                .map(Box::new),
+            name,
+            concrete,
+        })
+        .collect();
+    Ir {
+        trait_impl,
+        check,
+        instantiations,
+    }
+}
+
+/// Derives a deterministic name for a concrete implementation's test module from its trait and
+/// type, e.g. `Allocator_for_BadAllocator_1024`, falling back to a hash of the implementation
+/// when it can't be spelled out as an identifier.
+fn module_name(concrete: &ConcreteImpl) -> Ident {
+    let mut words = Vec::new();
+    let spelled_out = spell_out(concrete.trait_.to_token_stream(), &mut words) && {
+        words.push("for".to_owned());
+        spell_out(concrete.implementer.to_token_stream(), &mut words)
+    };
+    let name = if spelled_out {
+        words.join("_")
+    } else {
+        format!(
+            "{}_{:016x}",
+            trait_name(&concrete.trait_).ident.unraw(),
+            super::hash_tokens(concrete)
+        )
+    };
+    Ident::new(&name, Span::call_site())
+}
+
+/// Appends `_2`, `_3`, etc. to names already taken by an earlier implementation, as different
+/// types can be spelled out the same, e.g. `Pair<(u8, u16)>` and `Pair<u8, u16>`.
+fn disambiguate(names: Vec<Ident>) -> Vec<Ident> {
+    let derived = names.iter().map(Ident::to_string).collect::<HashSet<_>>();
+    let mut taken = HashSet::new();
+    (names.into_iter())
+        .map(|name| {
+            let mut unique = name.to_string();
+            // Suffixed names also mustn't take the name of a later implementation
+            let mut n = 1;
+            while taken.contains(&unique) || (n > 1 && derived.contains(&unique)) {
+                n += 1;
+                unique = format!("{name}_{n}");
+            }
+            taken.insert(unique.clone());
+            Ident::new(&unique, name.span())
+        })
+        .collect()
+}
+
+/// Appends words spelling out `tokens` to `words`, returning `false` if a token can't be
+/// spelled out as part of an identifier.
+pub fn spell_out(tokens: TokenStream, words: &mut Vec<String>) -> bool {
+    let mut tokens = tokens.into_iter();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) => words.push(ident.unraw().to_string()),
+            TokenTree::Literal(lit) => {
+                let lit = lit.to_string();
+                if !lit.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    return false;
+                }
+                words.push(lit);
+            }
+            TokenTree::Punct(punct) => match punct.as_char() {
+                '&' => words.push("ref".to_owned()),
+                '*' => words.push("ptr".to_owned()),
+                '!' => words.push("never".to_owned()),
+                '-' => words.push("neg".to_owned()),
+                // Lifetimes don't distinguish concrete implementations
+                '\'' => {
+                    tokens.next();
+                }
+                // Separators like `<`, `>`, `,`, and `::`
+                _ => {}
+            },
+            TokenTree::Group(group) => {
+                let stream = group.stream();
+                match group.delimiter() {
+                    Delimiter::Parenthesis if stream.is_empty() => {
+                        words.push("unit".to_owned());
+                    }
+                    Delimiter::Bracket => {
+                        let is_array = (stream.clone().into_iter()).any(
+                            |token| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ';'),
+                        );
+                        words.push(if is_array { "array" } else { "slice" }.to_owned());
+                    }
+                    _ => {}
+                }
+                if !spell_out(stream, words) {
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
+
+fn codegen(ir: Ir) -> TokenStream {
+    let Ir {
+        trait_impl,
+        check,
+        instantiations,
+    } = ir;
+    let instantiate_suites = instantiations.iter().map(|instantiation| {
+        // The trait's suite macro is exported under the trait's name, or that of its remote
+        // definition
+        let mut suite_macro = (instantiation.with.as_ref())
+            .unwrap_or(&instantiation.concrete.trait_)
+            .clone();
+        (suite_macro.segments)
+            .last_mut()
+            .expect("trait `Path`s contain at least one segment")
+            .arguments = PathArguments::None;
+        quote! {
+            #suite_macro! { #instantiation }
+        }
+    });
+    quote! {
+        #trait_impl
+        #check
+        #(#instantiate_suites)*
+    }
+}
+
+impl ToTokens for ImplCheck {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ImplCheck {
+            marker,
+            header,
+            generics,
+            self_ty,
+            const_params,
+            patterns,
+            inferred: _,
+            checked,
+        } = self;
+        let (wrappers, const_params): (Vec<_>, Vec<_>) = const_params.iter().cloned().unzip();
+        // Escape braces in const arguments, as the message is a format string
+        let message = format!(
+            "concrete implementations must instantiate `{}`",
+            header.replace('{', "{{").replace('}', "}}")
+        );
+        let impls = (1..=patterns.len()).map(|len| {
+            let (prefix, pattern) = (&patterns[..len - 1], &patterns[len - 1]);
+            if len == patterns.len() {
+                let (impl_generics, _, where_clause) = generics.split_for_impl();
+                return quote! {
+                    impl #impl_generics #marker<[#pattern; #len]>
+                        for ::core::marker::PhantomData<*const (#(#prefix,)*)>
+                    #where_clause
+                    {
+                        type Checked = #self_ty;
+
+                        fn check(self) -> ::core::marker::PhantomData<*const Self::Checked> {
+                            ::core::marker::PhantomData
+                        }
+                    }
+                };
+            }
+            // Earlier steps only have the parameters they mention, without bounds, which may
+            // mention the others
+            let params = generics.params.iter().filter_map(|param| match param {
+                GenericParam::Lifetime(param) => {
+                    let lifetime = &param.lifetime;
+                    Some(quote! { #lifetime })
+                }
+                GenericParam::Type(param) => {
+                    let ident = &param.ident;
+                    let unsized_ =
+                        is_maybe_sized(generics, ident).then(|| quote! { ?::core::marker::Sized });
+                    (patterns[..len].iter().any(|ty| mentions(ty.clone(), ident)))
+                        .then(|| quote! { #ident: #unsized_ })
+                }
+                GenericParam::Const(param) => {
+                    let ConstParam { ident, ty, .. } = param;
+                    (patterns[..len].iter().any(|ty| mentions(ty.clone(), ident)))
+                        .then(|| quote! { const #ident: #ty })
+                }
+            });
+            let checked = &patterns[..len];
+            quote! {
+                impl<#(#params),*> #marker<[#pattern; #len]>
+                    for ::core::marker::PhantomData<*const (#(#prefix,)*)>
+                {
+                    type Checked = (#(#checked,)*);
+
+                    fn check(self) -> ::core::marker::PhantomData<*const Self::Checked> {
+                        ::core::marker::PhantomData
+                    }
+                }
+            }
+        });
+        let ambiguous = [quote! { fn(T) }, quote! { fn() -> T }];
+        quote! {
+            #(
+                #[allow(dead_code, non_camel_case_types)]
+                struct #wrappers<#const_params>;
+            )*
+            #[allow(non_camel_case_types)]
+            #[diagnostic::on_unimplemented(
+                message = #message,
+                label = "not an instantiation of the annotated impl"
+            )]
+            trait #marker<T: ?::core::marker::Sized> {
+                type Checked: ?::core::marker::Sized;
+
+                fn check(self) -> ::core::marker::PhantomData<*const Self::Checked>;
+            }
+            #(#impls)*
+            // Leaves each step ambiguous until the types before it are inferred, so a mismatch
+            // is reported as a missing impl of the marker, rather than against the only impl
+            // that could apply
+            #(
+                #[diagnostic::do_not_recommend]
+                impl<T> #marker<T> for ::core::marker::PhantomData<*const #ambiguous> {
+                    type Checked = ();
+
+                    fn check(self) -> ::core::marker::PhantomData<*const Self::Checked> {
+                        ::core::marker::PhantomData
+                    }
+                }
+            )*
+            #(
+                const _: fn() = || {
+                    let _ = #checked;
+                };
+            )*
+        }
+        .to_tokens(tokens);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proc_macro2::Span;
+    use syn::{parse_quote, Ident};
+
+    use super::{disambiguate, module_name, ConcreteImpl};
+    use crate::fnv1a;
+
+    #[test]
+    fn module_names() {
+        let cases: [(ConcreteImpl, &str); 7] = [
+            (parse_quote!(Bar: Foo), "Foo_for_Bar"),
+            (
+                parse_quote!(BadAllocator<1024>: Allocator),
+                "Allocator_for_BadAllocator_1024",
+            ),
+            (parse_quote!((): Foo<u8>), "Foo_u8_for_unit"),
+            (parse_quote!(&'static str: a::Foo), "a_Foo_for_ref_str"),
+            (
+                parse_quote!(Vec<(u8, [u16; 2])>: Foo),
+                "Foo_for_Vec_u8_array_u16_2",
+            ),
+            (parse_quote!(Box<[r#type]>: Foo), "Foo_for_Box_slice_type"),
+            (
+                parse_quote!(*const Bar<{ -1 }>: Foo),
+                "Foo_for_ptr_const_Bar_neg_1",
+            ),
+        ];
+        for (concrete, expected) in cases {
+            assert_eq!(module_name(&concrete), expected);
+        }
+    }
+
+    #[test]
+    fn module_name_hash_fallback() {
+        let concrete: ConcreteImpl = parse_quote!(Bar<"str">: Foo);
+        let hash = fnv1a("Bar < \"str\" > : Foo ");
+        assert_eq!(module_name(&concrete), format!("Foo_{hash:016x}"));
+
+        let concrete: ConcreteImpl = parse_quote!(Bar<{ "str" }>: r#Foo);
+        let hash = fnv1a("Bar < {\"str\" } > : r#Foo ");
+        assert_eq!(module_name(&concrete), format!("Foo_{hash:016x}"));
+    }
+
+    #[test]
+    fn disambiguated_names() {
+        let names = ["Foo_for_Bar", "Foo_for_Bar_2", "Foo_for_Bar", "Foo_for_Bar"]
+            .map(|name| Ident::new(name, Span::call_site()));
+        assert_eq!(
+            disambiguate(names.into()),
+            [
+                "Foo_for_Bar",
+                "Foo_for_Bar_2",
+                "Foo_for_Bar_3",
+                "Foo_for_Bar_4"
+            ]
+        );
+    }
+}
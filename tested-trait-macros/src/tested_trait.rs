@@ -0,0 +1,1982 @@
+use std::{borrow::Borrow, collections::HashSet, fmt::Display, str::FromStr};
+
+use manyhow::{bail, error_message};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    ext::IdentExt,
+    meta::ParseNestedMeta,
+    parse::{Parse, Parser},
+    parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute, Block, ConstParam, Expr, ExprLit, FnArg, GenericParam, Ident, Item, ItemTrait,
+    LifetimeParam, Lit, LitInt, LitStr, Meta, MetaNameValue, Pat, PatType, Path, PredicateType,
+    Receiver, ReturnType, Signature, Token, TraitBoundModifier, TraitItem, TraitItemFn, Type,
+    TypeParam, TypeParamBound, Visibility, WhereClause, WherePredicate,
+};
+
+use super::{
+    suite::{Inclusion, Marker, Suite, SuiteParam, SuiteTest, TypeList},
+    AssociatedTestFnIdent, AssociatedTestProbeIdent, HandleAccessorIdent,
+};
+
+pub fn tested_trait(args: TokenStream, item: TokenStream) -> manyhow::Result<TokenStream> {
+    let ast = parse(args, item)?;
+    let model = analyze(ast)?;
+    let ir = lower(model);
+    Ok(codegen(ir))
+}
+
+/// Generates the tests of a `#[trait_tests]` module, given the trait holding them that
+/// extends the trait they're for.
+pub fn out_of_line_tests(args: TokenStream, trait_defn: ItemTrait) -> manyhow::Result<TokenStream> {
+    let mut ast = parse(args, trait_defn.into_token_stream())?;
+    if let Some(path) = (ast.args.include_supertraits.as_ref()).and_then(|paths| paths.first()) {
+        bail!(
+            path,
+            "#[trait_tests] modules cannot include the tests of supertraits"
+        )
+    }
+    if let Some(path) = ast.args.tests.first() {
+        bail!(
+            path,
+            "#[trait_tests] modules cannot include other #[trait_tests] modules"
+        )
+    }
+    ast.args.out_of_line = true;
+    let model = analyze(ast)?;
+    let ir = lower(model);
+    Ok(codegen(ir))
+}
+
+const MACRO: &str = "tested_trait";
+
+struct Ast {
+    args: Args,
+    trait_defn: ItemTrait,
+}
+
+/// `#[tested_trait(parallel, timeout = 500, executor = path::to::block_on,
+/// feature = "conformance-tests", proptest(cases = 256), quickcheck(tests = 1000),
+/// types(U = [u8, String]), include_supertraits(Bar, Baz), tests = self::extra,
+/// remote = path::to::Foreign)]`
+#[derive(Default)]
+struct Args {
+    /// Whether to run the tests in parallel when in an integration test, which is the only place
+    /// it has an effect: the tests generated otherwise already run in parallel
+    parallel: bool,
+    /// Timeout in milliseconds for tests without their own `#[timeout]`
+    timeout: Option<LitInt>,
+    /// Function driving `async` tests' futures to completion
+    executor: Option<Path>,
+    /// Cargo feature of the trait's crate that the tests are only compiled with (or in its
+    /// own unit tests)
+    feature: Option<LitStr>,
+    /// Defaults for `#[proptest]` tests
+    proptest: ProptestArgs,
+    /// Number of passing tests `#[quickcheck]` tests require by default
+    quickcheck_tests: Option<LitInt>,
+    /// The arguments generic tests are instantiated with by default
+    types: Vec<TypeList>,
+    /// The supertraits whose tests are included in the trait's, or all of them if none are
+    /// listed
+    include_supertraits: Option<Vec<Path>>,
+    /// The `#[trait_tests]` modules whose tests are included in the trait's
+    tests: Vec<Path>,
+    /// Whether the trait holds the tests of a `#[trait_tests]` module
+    out_of_line: bool,
+    /// The foreign trait that the trait is a remote definition of, holding its tests
+    remote: Option<Path>,
+}
+
+/// `proptest(cases = 256, config = path::to::config())`
+#[derive(Default)]
+struct ProptestArgs {
+    /// Number of cases to run, overriding `config`'s
+    cases: Option<LitInt>,
+    /// `proptest::test_runner::Config` to run the tests with
+    config: Option<Expr>,
+}
+
+fn parse(args: TokenStream, item: TokenStream) -> manyhow::Result<Ast> {
+    let trait_defn = syn::parse2(item).map_err(|err| {
+        error_message!(
+            err.span(),
+            "#[{MACRO}] can only be used to annotate trait definitions"
+        )
+    })?;
+    let mut parsed_args = Args::default();
+    syn::meta::parser(|meta| {
+        if meta.path.is_ident("parallel") {
+            parsed_args.parallel = true;
+            Ok(())
+        } else if meta.path.is_ident("timeout") {
+            // `timeout = 500`
+            if meta.input.peek(Token![=]) {
+                let lit: LitInt = meta.value()?.parse()?;
+                lit.base10_parse::<u64>()?;
+                parsed_args.timeout = Some(lit);
+                return Ok(());
+            }
+            let mut ms = None;
+            meta.parse_nested_meta(|meta| parse_timeout_ms(&meta, &mut ms))?;
+            let ms = ms.ok_or_else(|| {
+                meta.error("expected `timeout = <milliseconds>` or `timeout(ms = <milliseconds>)`")
+            })?;
+            parsed_args.timeout = Some(ms);
+            Ok(())
+        } else if meta.path.is_ident("executor") {
+            parsed_args.executor = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("feature") {
+            parsed_args.feature = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("proptest") {
+            meta.parse_nested_meta(|meta| {
+                if meta.path.is_ident("cases") {
+                    parsed_args.proptest.cases = Some(parse_count::<u32>(&meta)?);
+                    Ok(())
+                } else if meta.path.is_ident("config") {
+                    parsed_args.proptest.config = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `cases = <number>` or `config = <expr>`"))
+                }
+            })
+        } else if meta.path.is_ident("quickcheck") {
+            meta.parse_nested_meta(|meta| {
+                if meta.path.is_ident("tests") {
+                    parsed_args.quickcheck_tests = Some(parse_count::<u64>(&meta)?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `tests = <number>`"))
+                }
+            })
+        } else if meta.path.is_ident("types") {
+            let content;
+            syn::parenthesized!(content in meta.input);
+            let lists = content.parse_terminated(TypeList::parse, Token![,])?;
+            parsed_args.types.extend(lists);
+            Ok(())
+        } else if meta.path.is_ident("include_supertraits") {
+            let included = parsed_args.include_supertraits.get_or_insert_with(Vec::new);
+            if meta.input.peek(syn::token::Paren) {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                included.extend(content.parse_terminated(Path::parse, Token![,])?);
+            }
+            Ok(())
+        } else if meta.path.is_ident("tests") {
+            parsed_args.tests.push(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("remote") {
+            parsed_args.remote = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error(format_args!("unsupported #[{MACRO}] argument")))
+        }
+    })
+    .parse2(args)?;
+    Ok(Ast {
+        args: parsed_args,
+        trait_defn,
+    })
+}
+
+/// Parses `ms = 500` within `timeout(..)`.
+fn parse_timeout_ms(meta: &ParseNestedMeta, ms: &mut Option<LitInt>) -> syn::Result<()> {
+    if meta.path.is_ident("ms") {
+        let lit: LitInt = meta.value()?.parse()?;
+        lit.base10_parse::<u64>()?;
+        *ms = Some(lit);
+        Ok(())
+    } else {
+        Err(meta.error("expected `ms = <milliseconds>`"))
+    }
+}
+
+/// Parses `cases = 256` within `proptest(..)` or `tests = 1000` within `quickcheck(..)`.
+fn parse_count<N>(meta: &ParseNestedMeta) -> syn::Result<LitInt>
+where
+    N: FromStr,
+    N::Err: Display,
+{
+    let lit: LitInt = meta.value()?.parse()?;
+    lit.base10_parse::<N>()?;
+    Ok(lit)
+}
+
+struct Model {
+    parallel: bool,
+    out_of_line: bool,
+    executor: Option<Path>,
+    feature: Option<LitStr>,
+    params: Vec<SuiteParam>,
+    types: Vec<TypeList>,
+    /// The supertraits whose tests are included in the trait's, as written in its definition
+    supertraits: Vec<Path>,
+    /// The `#[trait_tests]` modules whose tests are included in the trait's
+    tests_modules: Vec<Path>,
+    /// The foreign trait that the trait is a remote definition of, which has been added to
+    /// its supertraits
+    remote: Option<Path>,
+    tests: Vec<AssociatedTest>,
+    trait_defn: ItemTrait,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct AssociatedTest {
+    kind: TestKind,
+    ident: Ident,
+    /// Whether the test is an `async fn`, whose body is run by the suite's executor
+    is_async: bool,
+    /// `self`, `&self`, or `&mut self`, if the test is run against the implementation's
+    /// fixtures
+    receiver: Option<Receiver>,
+    /// Type and const parameters, which the test is instantiated over lists of arguments for
+    generics: Vec<GenericParam>,
+    bounds: Option<WhereClause>,
+    /// Whether the test is skipped for implementations that don't satisfy its `bounds`
+    optional: bool,
+    ignore: Option<Ignore>,
+    /// Milliseconds after which the test fails if it hasn't finished
+    timeout: Option<LitInt>,
+    /// Whether the test must not run concurrently with other associated tests
+    serial: bool,
+    /// Whether the test is a property-based test, whose arguments are generated
+    property: Option<Property>,
+    body: Block,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Property {
+    Proptest(Proptest),
+    Quickcheck(Quickcheck),
+}
+
+/// `#[proptest]` or `#[proptest(cases = 64)]`, with the test's arguments generated by
+/// proptest
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Proptest {
+    /// Number of cases to run, defaulting to the trait's
+    cases: Option<LitInt>,
+    /// The trait's `proptest::test_runner::Config`
+    config: Option<Expr>,
+    args: Vec<ProptestArg>,
+}
+
+/// `#[quickcheck]` or `#[quickcheck(tests = 1000)]`, with the test's arguments generated by
+/// quickcheck
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Quickcheck {
+    /// Number of passing tests required, defaulting to the trait's
+    tests: Option<LitInt>,
+    args: Vec<PatType>,
+    /// The property's result, e.g. `bool` or `quickcheck::TestResult`
+    output: Box<Type>,
+}
+
+/// `x: u32` or `#[strategy(0..10u32)] x: u32`
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ProptestArg {
+    pat: Box<Pat>,
+    ty: Box<Type>,
+    /// The argument's strategy, if not `proptest::arbitrary::any::<ty>()`
+    strategy: Option<Expr>,
+}
+
+/// `#[ignore]` or `#[ignore = "reason"]`
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Ignore {
+    reason: Option<LitStr>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TestKind {
+    Standard,
+    ReturnsResult { output: Box<Type> },
+    ShouldPanic { expected: Option<Expr> },
+}
+
+fn analyze(ast: Ast) -> manyhow::Result<Model> {
+    let Ast {
+        args,
+        mut trait_defn,
+    } = ast;
+    let is_test = |item: &TraitItemFn| match find_attr(&item.attrs, "test") {
+        Some(attr) => attr.meta.require_path_only().is_ok(),
+        None => false,
+    };
+    let num_tests = (trait_defn.items)
+        .iter()
+        .filter(|item| matches!(item, TraitItem::Fn(item) if is_test(item)))
+        .count();
+    let mut tests = Vec::with_capacity(num_tests);
+    let mut items = Vec::with_capacity(trait_defn.items.len() - num_tests);
+    for item in trait_defn.items {
+        match item {
+            TraitItem::Fn(item) if is_test(&item) => tests.extend(analyze_test(item, &args)?),
+            item => items.push(item),
+        }
+    }
+    trait_defn.items = items;
+
+    // A remote definition is implemented for all of the foreign trait's implementations, so
+    // they can't be required to provide any of its items
+    if let Some(remote) = &args.remote {
+        check_remote_items(&trait_defn, remote)?;
+    }
+
+    // Check the same name isn't used for multiple tests
+    let mut test_idents = HashSet::with_capacity(tests.len());
+    for test in &tests {
+        if !test_idents.insert(&test.ident) {
+            bail!(
+                test.ident,
+                "the test `{}` is defined multiple times",
+                test.ident
+            )
+        }
+    }
+
+    check_type_lists(&args.types, &tests)?;
+
+    let params = suite_params(&trait_defn.generics.params)?;
+    let supertraits = match &args.include_supertraits {
+        Some(included) => included_supertraits(&trait_defn, included)?,
+        None => Vec::new(),
+    };
+    // The tests can use the foreign trait's items through `Self`
+    if let Some(remote) = &args.remote {
+        (trait_defn.supertraits).push(parse_quote! { #remote });
+    }
+
+    Ok(Model {
+        parallel: args.parallel,
+        out_of_line: args.out_of_line,
+        executor: args.executor,
+        feature: args.feature,
+        params,
+        types: args.types,
+        supertraits,
+        tests_modules: args.tests,
+        remote: args.remote,
+        tests,
+        trait_defn,
+    })
+}
+
+fn find_attr<Attrs>(attrs: Attrs, name: &str) -> Option<Attrs::Item>
+where
+    Attrs: IntoIterator,
+    Attrs::Item: Borrow<Attribute>,
+{
+    (attrs.into_iter()).find(|attr| attr.borrow().meta.path().is_ident(name))
+}
+
+/// Analyzes an associated `#[test]`, which is a test per `#[case]` if it has any.
+fn analyze_test(item: TraitItemFn, args: &Args) -> manyhow::Result<Vec<AssociatedTest>> {
+    let span = item.span();
+    let body = item
+        .default
+        .ok_or_else(|| error_message!(span, "associated #[test]s must have a body"))?;
+    let mut returns_result = match &item.sig.output {
+        // fn test() {}
+        ReturnType::Default => None,
+        // fn test() -> () {}
+        ReturnType::Type(_, ty) if matches!(ty.as_ref(), Type::Tuple(tup) if tup.elems.is_empty()) => {
+            None
+        }
+        // fn test() -> _ {}
+        // Assume return type is a result
+        ReturnType::Type(_, ty) => Some(ty.clone()),
+    };
+    let optional = match find_attr(&item.attrs, "optional") {
+        Some(attr) => {
+            attr.meta.require_path_only()?;
+            if item.sig.generics.where_clause.is_none() {
+                bail!(
+                    attr,
+                    "#[optional] tests must have a `where` clause to make them optional"
+                )
+            }
+            true
+        }
+        None => false,
+    };
+    let ignore = find_attr(&item.attrs, "ignore")
+        .map(parse_ignore)
+        .transpose()?;
+    let timeout = match find_attr(&item.attrs, "timeout") {
+        Some(attr) => Some(parse_timeout(attr)?),
+        None => args.timeout.clone(),
+    };
+    let serial = match find_attr(&item.attrs, "serial") {
+        Some(attr) => {
+            attr.meta.require_path_only()?;
+            true
+        }
+        None => false,
+    };
+    let proptest = find_attr(&item.attrs, "proptest")
+        .map(|attr| parse_proptest(attr, &item.sig, args))
+        .transpose()?;
+    let quickcheck = match find_attr(&item.attrs, "quickcheck") {
+        Some(attr) => {
+            if proptest.is_some() {
+                bail!(attr, "tests cannot be both #[proptest] and #[quickcheck]")
+            }
+            // The test's return type is the property's result
+            let output = returns_result.take().unwrap_or_else(|| parse_quote!(()));
+            Some(parse_quickcheck(attr, &item.sig, args, output)?)
+        }
+        None => None,
+    };
+    let property = proptest.or(quickcheck);
+    let receiver = test_receiver(&item.sig)?;
+    let generics = check_test_generics(&item.sig.generics.params)?;
+    let params = (item.sig.inputs.iter())
+        .filter_map(|input| match input {
+            FnArg::Receiver(_) => None,
+            FnArg::Typed(param) => Some(param),
+        })
+        .collect::<Vec<_>>();
+    let cases = (item.attrs.iter())
+        .filter(|attr| {
+            let first = attr.path().segments.first();
+            first.is_some_and(|segment| segment.ident == "case")
+        })
+        .map(|attr| parse_case(attr, &params))
+        .collect::<manyhow::Result<Vec<_>>>()?;
+    if let (Some(case), Some(_)) = (cases.first(), &property) {
+        bail!(
+            case.attr,
+            "#[proptest] and #[quickcheck] tests cannot have #[case]s"
+        )
+    }
+    if let (None, true, Some(param)) = (&property, cases.is_empty(), params.first()) {
+        bail!(
+            param,
+            "only tests with #[case]s and #[proptest] and #[quickcheck] tests can take arguments"
+        )
+    }
+    let should_panic = find_attr(&item.attrs, "should_panic");
+    let kind = test_kind(span, returns_result, should_panic, property.as_ref())?;
+    let test = AssociatedTest {
+        kind,
+        is_async: item.sig.asyncness.is_some(),
+        receiver,
+        generics,
+        ident: item.sig.ident,
+        bounds: item.sig.generics.where_clause,
+        optional,
+        ignore,
+        timeout,
+        serial,
+        property,
+        body,
+    };
+    if cases.is_empty() {
+        Ok(vec![test])
+    } else {
+        Ok(expand_cases(&test, cases, &params))
+    }
+}
+
+/// The kind of a test returning `returns_result`, with the `should_panic` attribute if any.
+fn test_kind(
+    span: Span,
+    returns_result: Option<Box<Type>>,
+    should_panic: Option<&Attribute>,
+    property: Option<&Property>,
+) -> manyhow::Result<TestKind> {
+    let should_panic = should_panic.map(parse_should_panic).transpose()?;
+    let kind = match (returns_result, should_panic) {
+        (None, None) => TestKind::Standard,
+        (None, Some(expected)) => TestKind::ShouldPanic { expected },
+        (Some(output), None) => TestKind::ReturnsResult { output },
+        (Some(_), Some(_)) => {
+            bail!(span, "#[should_panic] tests cannot return Result")
+        }
+    };
+    match property {
+        Some(Property::Proptest(_)) if kind != TestKind::Standard => bail!(
+            span,
+            "#[proptest] tests cannot return Result or be #[should_panic]"
+        ),
+        Some(Property::Quickcheck(_)) if kind != TestKind::Standard => {
+            bail!(span, "#[quickcheck] tests cannot be #[should_panic]")
+        }
+        _ => Ok(kind),
+    }
+}
+
+/// Expands a test with `#[case]`s into a test per case, which binds the case's arguments to
+/// the test's `params`.
+fn expand_cases(
+    test: &AssociatedTest,
+    cases: Vec<Case>,
+    params: &[&PatType],
+) -> Vec<AssociatedTest> {
+    (cases.into_iter().enumerate())
+        .map(|(i, case)| {
+            let ident = match &case.description {
+                Some(description) => {
+                    format_ident!("{}_case_{}_{description}", test.ident, i + 1)
+                }
+                None => format_ident!("{}_case_{}", test.ident, i + 1),
+            };
+            let pats = params.iter().map(|param| &param.pat);
+            let tys = params.iter().map(|param| &param.ty);
+            let args = case.args.iter();
+            let body = &test.body;
+            AssociatedTest {
+                ident,
+                body: parse_quote! {{
+                    #(let #pats: #tys = #args;)*
+                    #body
+                }},
+                ..test.clone()
+            }
+        })
+        .collect()
+}
+
+/// The `self`, `&self`, or `&mut self` taken by the test with signature `sig`, if any.
+fn test_receiver(sig: &Signature) -> manyhow::Result<Option<Receiver>> {
+    match sig.inputs.first() {
+        Some(FnArg::Receiver(receiver)) if receiver.colon_token.is_some() => bail!(
+            receiver,
+            "tests can only take `self`, `&self`, or `&mut self`"
+        ),
+        Some(FnArg::Receiver(receiver)) => Ok(Some(receiver.clone())),
+        _ => Ok(None),
+    }
+}
+
+/// Parses `#[ignore]` or `#[ignore = "reason"]`.
+fn parse_ignore(attr: &Attribute) -> manyhow::Result<Ignore> {
+    let reason = match &attr.meta {
+        Meta::Path(_) => None,
+        // #[ignore = "reason"]
+        Meta::NameValue(MetaNameValue {
+            value:
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(reason),
+                    ..
+                }),
+            ..
+        }) => Some(reason.clone()),
+        Meta::List(_) | Meta::NameValue(_) => bail!(attr, "invalid #[ignore] syntax"),
+    };
+    Ok(Ignore { reason })
+}
+
+/// Parses `#[timeout = 500]` or `#[timeout(ms = 500)]`.
+fn parse_timeout(attr: &Attribute) -> manyhow::Result<LitInt> {
+    const EXPECTED: &str =
+        "expected `#[timeout = <milliseconds>]` or `#[timeout(ms = <milliseconds>)]`";
+    match &attr.meta {
+        // #[timeout = 500]
+        Meta::NameValue(MetaNameValue {
+            value: Expr::Lit(ExprLit {
+                lit: Lit::Int(ms), ..
+            }),
+            ..
+        }) => {
+            ms.base10_parse::<u64>()?;
+            Ok(ms.clone())
+        }
+        Meta::List(_) => {
+            let mut ms = None;
+            attr.parse_nested_meta(|meta| parse_timeout_ms(&meta, &mut ms))?;
+            Ok(ms.ok_or_else(|| error_message!(attr, "{EXPECTED}"))?)
+        }
+        Meta::Path(_) | Meta::NameValue(_) => bail!(attr, "{EXPECTED}"),
+    }
+}
+
+/// Parses `#[should_panic]`, `#[should_panic = "message"]`, or
+/// `#[should_panic(expected = "message")]` into the expected message, if any.
+fn parse_should_panic(attr: &Attribute) -> manyhow::Result<Option<Expr>> {
+    let expected = match &attr.meta {
+        Meta::Path(_) => None,
+        // #[should_panic = ""]
+        Meta::NameValue(meta) => Some(meta.value.clone()),
+        // #[should_panic(expected = "")]
+        Meta::List(meta) => {
+            let meta = meta.parse_args::<MetaNameValue>()?;
+            if meta.path.is_ident("expected") {
+                Some(meta.value)
+            } else {
+                bail!(meta, "invalid #[should_panic] syntax")
+            }
+        }
+    };
+    // Without `std`, the test harness checks the panic message, which it requires to be a
+    // string literal
+    if let Some(expected) = &expected {
+        if !cfg!(feature = "std") && expected_message(expected).is_none() {
+            bail!(
+                expected,
+                "the expected panic message must be a string literal without tested-trait's \
+                 `std` feature"
+            )
+        }
+    }
+    Ok(expected)
+}
+
+/// Parses `#[proptest]` or `#[proptest(cases = 64)]` on the test with signature `sig`,
+/// whose arguments are generated.
+fn parse_proptest(attr: &Attribute, sig: &Signature, args: &Args) -> manyhow::Result<Property> {
+    if !cfg!(feature = "proptest") {
+        bail!(
+            attr,
+            "#[proptest] tests require tested-trait's `proptest` feature"
+        )
+    }
+    if let Some(asyncness) = sig.asyncness {
+        bail!(asyncness, "#[proptest] tests cannot be async")
+    }
+    let mut cases = None;
+    if !matches!(attr.meta, Meta::Path(_)) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("cases") {
+                cases = Some(parse_count::<u32>(&meta)?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `cases = <number>`"))
+            }
+        })?;
+    }
+    if sig.inputs.is_empty() {
+        bail!(
+            sig.ident,
+            "#[proptest] tests must take the arguments to generate"
+        )
+    }
+    let proptest_args = (sig.inputs.iter())
+        .map(|input| match input {
+            FnArg::Receiver(receiver) => {
+                bail!(receiver, "#[proptest] tests cannot take `self`")
+            }
+            FnArg::Typed(arg) => Ok(ProptestArg {
+                pat: arg.pat.clone(),
+                ty: arg.ty.clone(),
+                strategy: find_attr(&arg.attrs, "strategy")
+                    .map(Attribute::parse_args)
+                    .transpose()?,
+            }),
+        })
+        .collect::<manyhow::Result<_>>()?;
+    Ok(Property::Proptest(Proptest {
+        cases: cases.or_else(|| args.proptest.cases.clone()),
+        config: args.proptest.config.clone(),
+        args: proptest_args,
+    }))
+}
+
+/// Parses `#[quickcheck]` or `#[quickcheck(tests = 1000)]` on the test with signature `sig`,
+/// whose arguments are generated and which returns `output`.
+fn parse_quickcheck(
+    attr: &Attribute,
+    sig: &Signature,
+    args: &Args,
+    output: Box<Type>,
+) -> manyhow::Result<Property> {
+    if !cfg!(feature = "quickcheck") {
+        bail!(
+            attr,
+            "#[quickcheck] tests require tested-trait's `quickcheck` feature"
+        )
+    }
+    if let Some(asyncness) = sig.asyncness {
+        bail!(asyncness, "#[quickcheck] tests cannot be async")
+    }
+    let mut tests = None;
+    if !matches!(attr.meta, Meta::Path(_)) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("tests") {
+                tests = Some(parse_count::<u64>(&meta)?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `tests = <number>`"))
+            }
+        })?;
+    }
+    if sig.inputs.is_empty() {
+        bail!(
+            sig.ident,
+            "#[quickcheck] tests must take the arguments to generate"
+        )
+    }
+    let quickcheck_args = (sig.inputs.iter())
+        .map(|input| match input {
+            FnArg::Receiver(receiver) => {
+                bail!(receiver, "#[quickcheck] tests cannot take `self`")
+            }
+            FnArg::Typed(arg) => Ok(arg.clone()),
+        })
+        .collect::<manyhow::Result<_>>()?;
+    Ok(Property::Quickcheck(Quickcheck {
+        tests: tests.or_else(|| args.quickcheck_tests.clone()),
+        args: quickcheck_args,
+        output,
+    }))
+}
+
+/// Checks a test's generic parameters, which it's instantiated over lists of arguments for.
+fn check_test_generics<'a>(
+    params: impl IntoIterator<Item = &'a GenericParam>,
+) -> manyhow::Result<Vec<GenericParam>> {
+    let mut generics = Vec::new();
+    for param in params {
+        match param {
+            GenericParam::Lifetime(param) => {
+                bail!(param, "associated tests cannot have lifetime parameters")
+            }
+            GenericParam::Type(TypeParam {
+                eq_token: Some(eq_token),
+                ..
+            })
+            | GenericParam::Const(ConstParam {
+                eq_token: Some(eq_token),
+                ..
+            }) => bail!(
+                eq_token,
+                "the generic parameters of associated tests cannot have defaults"
+            ),
+            GenericParam::Const(param) => {
+                const_wrapper(&param.ty)?;
+            }
+            GenericParam::Type(_) => {}
+        }
+        generics.push(param.clone());
+    }
+    Ok(generics)
+}
+
+/// Checks a remote definition of `remote` doesn't require its implementations to provide
+/// any items.
+fn check_remote_items(trait_defn: &ItemTrait, remote: &Path) -> manyhow::Result<()> {
+    let remote = super::pretty_print(remote);
+    for item in &trait_defn.items {
+        match item {
+            TraitItem::Fn(TraitItemFn {
+                default: None, sig, ..
+            }) => bail!(
+                sig.ident,
+                "`{}` must have a body, as remote definitions are implemented for all \
+                 implementations of `{remote}`",
+                sig.ident
+            ),
+            TraitItem::Const(item) if item.default.is_none() => bail!(
+                item.ident,
+                "`{}` must have a value, as remote definitions are implemented for all \
+                 implementations of `{remote}`",
+                item.ident
+            ),
+            TraitItem::Type(item) => {
+                bail!(item, "remote definitions cannot have associated types")
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Checks each type list is for a generic parameter of some test, and only given once.
+fn check_type_lists(types: &[TypeList], tests: &[AssociatedTest]) -> manyhow::Result<()> {
+    let mut listed = HashSet::with_capacity(types.len());
+    for list in types {
+        let param = &list.param;
+        if !listed.insert(param) {
+            bail!(param, "the types of `{param}` are listed multiple times")
+        }
+        let has_param = |test: &AssociatedTest| {
+            (test.generics.iter()).any(|generic| match generic {
+                GenericParam::Type(generic) => generic.ident == *param,
+                GenericParam::Const(generic) => generic.ident == *param,
+                GenericParam::Lifetime(_) => false,
+            })
+        };
+        if !tests.iter().any(has_param) {
+            bail!(
+                param,
+                "no associated test has a generic parameter `{param}`"
+            )
+        }
+    }
+    Ok(())
+}
+
+/// Finds the supertraits whose tests are included in the trait's: those `included`, or all
+/// of them if none are listed.
+///
+/// Fails if an included trait isn't a supertrait, or if there are none to include.
+fn included_supertraits(trait_defn: &ItemTrait, included: &[Path]) -> manyhow::Result<Vec<Path>> {
+    let trait_name = &trait_defn.ident;
+    let supertraits = (trait_defn.supertraits.iter())
+        .filter_map(|bound| match bound {
+            TypeParamBound::Trait(bound) if matches!(bound.modifier, TraitBoundModifier::None) => {
+                Some(&bound.path)
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if included.is_empty() {
+        if supertraits.is_empty() {
+            bail!(
+                trait_defn.ident,
+                "`{trait_name}` has no supertraits whose tests can be included"
+            )
+        }
+        return Ok(supertraits.into_iter().cloned().collect());
+    }
+    // Generic arguments can be omitted when listing the supertraits
+    let idents = |path: &Path| {
+        (path.segments.iter())
+            .map(|segment| segment.ident.clone())
+            .collect::<Vec<_>>()
+    };
+    (included.iter())
+        .map(|path| {
+            match supertraits
+                .iter()
+                .find(|bound| idents(bound) == idents(path))
+            {
+                Some(bound) => Ok((*bound).clone()),
+                None => bail!(
+                    path,
+                    "`{}` is not a supertrait of `{trait_name}`",
+                    super::pretty_print(path)
+                ),
+            }
+        })
+        .collect()
+}
+
+struct Ir {
+    trait_defn: ItemTrait,
+    new_trait_items: Vec<TraitItem>,
+    new_items: Vec<Item>,
+    suite: Suite,
+    /// The supertraits (as written in the trait's definition) and the traits of the
+    /// `#[trait_tests]` modules whose tests are included in the trait's
+    included: Vec<Path>,
+    feature: Option<LitStr>,
+}
+
+fn lower(model: Model) -> Ir {
+    let Model {
+        parallel,
+        out_of_line,
+        executor,
+        feature,
+        params,
+        types,
+        supertraits,
+        tests_modules,
+        remote,
+        trait_defn,
+        tests,
+    } = model;
+    let generics = TraitGenerics::new(&trait_defn, &params);
+    let mut new_items = Vec::new();
+    let handle_methods = (tests.iter())
+        .map(|test| {
+            if test.optional {
+                let (probe_fn, probe_items) = optional_test_probe(&trait_defn, &generics, test);
+                new_items.extend(probe_items);
+                probe_fn
+            } else {
+                test_fn_getter(&trait_defn, &generics, test)
+            }
+        })
+        .collect::<Vec<_>>();
+    let test_fns = (tests.iter())
+        .map(|test| test_fn(&trait_defn.ident, executor.as_ref(), test))
+        .collect::<Vec<_>>();
+    new_items.extend(sealed_tests(
+        &trait_defn,
+        &generics,
+        &test_fns,
+        &handle_methods,
+    ));
+    if remote.is_some() {
+        new_items.push(remote_impl(&trait_defn, &generics));
+    }
+    let mut new_trait_items = vec![handle_accessor(&trait_defn)];
+    new_trait_items.extend(tests_modules.iter().map(tests_module_handle_accessor));
+    let accessor_id = suite_accessor(&trait_defn, &generics).map(|(id, method)| {
+        new_trait_items.push(method);
+        id
+    });
+    let suite = Suite {
+        disabled: None,
+        parallel,
+        out_of_line,
+        remote,
+        params,
+        types,
+        accessor_id,
+        included: Vec::new(),
+        trait_name: trait_defn.ident.clone(),
+        tests: tests.into_iter().map(suite_test).collect(),
+    };
+
+    let included = (supertraits.into_iter())
+        .chain(tests_modules.iter().map(super::out_of_line_trait))
+        .collect();
+    Ir {
+        trait_defn,
+        new_trait_items,
+        new_items,
+        suite,
+        included,
+        feature,
+    }
+}
+
+/// Generates the function running `test`, which is generic over its generic parameters and
+/// has its bounds.
+fn test_fn(trait_ident: &Ident, executor: Option<&Path>, test: &AssociatedTest) -> TokenStream {
+    let AssociatedTest {
+        kind,
+        ident,
+        is_async,
+        receiver,
+        generics,
+        bounds,
+        optional: _,
+        ignore: _,
+        timeout: _,
+        serial: _,
+        property,
+        body,
+    } = test;
+    let body = if *is_async {
+        let executor = executor.map_or_else(
+            || quote! { ::tested_trait::__private::block_on },
+            ToTokens::to_token_stream,
+        );
+        quote! { #executor(async move #body) }
+    } else {
+        body.to_token_stream()
+    };
+    let run_test = match (kind, property) {
+        (TestKind::Standard, Some(Property::Proptest(proptest))) => {
+            proptest_test(trait_ident, ident, proptest, &body)
+        }
+        (TestKind::Standard, Some(Property::Quickcheck(quickcheck))) => {
+            quickcheck_test(trait_ident, ident, quickcheck, &body)
+        }
+        (TestKind::Standard, None) => quote! {
+            let (): () = #body;
+        },
+        (TestKind::ReturnsResult { output }, _) => quote! {
+            let result: #output = #body;
+            result.unwrap();
+        },
+        // Without `std`, panics can't be caught, so the test harness catches them
+        (TestKind::ShouldPanic { .. }, _) if !cfg!(feature = "std") => quote! {
+            let (): () = #body;
+        },
+        (TestKind::ShouldPanic { expected }, _) => {
+            let check_panic = expected.as_ref().map(|expected| {
+                quote! {
+                    let err = ::core::ops::Deref::deref(&err);
+                    let message = <dyn ::core::any::Any>::downcast_ref::<::tested_trait::__private::String>(err)
+                        .map(|s| s.as_str())
+                        .or_else(|| <dyn ::core::any::Any>::downcast_ref::<&str>(err).copied())
+                        .unwrap_or_else(|| ::core::panic!(
+                            "expected panic with string value, found non-string value"
+                        ));
+                    ::core::assert!(message.contains(#expected));
+                }
+            });
+            quote! {
+                // The test's `self` doesn't outlive a panic
+                match ::tested_trait::__private::catch_unwind(
+                    ::core::panic::AssertUnwindSafe(|| #body),
+                ) {
+                    ::core::result::Result::Ok(()) => {
+                        ::core::panic!("test did not panic as expected")
+                    }
+                    ::core::result::Result::Err(err) => {
+                        #check_panic
+                    }
+                }
+            }
+        }
+    };
+    let bounds = (bounds.iter()).flat_map(|bounds| &bounds.predicates);
+    let test_fn_ident = AssociatedTestFnIdent(ident);
+    quote! {
+        // Tests that only panic would otherwise trigger this lint in `let (): () = ...`
+        #[allow(clippy::diverging_sub_expression)]
+        fn #test_fn_ident<#(#generics),*>(#receiver)
+        where
+            Self: ::core::marker::Sized,
+            #(#bounds),*
+        {
+            #run_test
+        }
+    }
+}
+
+/// Describes `test` to the suite macro.
+fn suite_test(test: AssociatedTest) -> SuiteTest {
+    SuiteTest {
+        kind: match test.kind {
+            TestKind::Standard => None,
+            TestKind::ReturnsResult { .. } => Some(Ident::new("ReturnsResult", Span::call_site())),
+            TestKind::ShouldPanic { .. } => Some(Ident::new("ShouldPanic", Span::call_site())),
+        },
+        optional_bounds: test.optional.then(|| {
+            (individual_bounds(test.bounds.as_ref()).iter())
+                .map(|predicate| LitStr::new(&super::pretty_print(predicate), Span::call_site()))
+                .collect()
+        }),
+        ignore: match test.ignore {
+            Some(Ignore {
+                reason: Some(reason),
+            }) => Marker::WithMessage(reason),
+            Some(Ignore { reason: None }) => Marker::Present,
+            None => Marker::Absent,
+        },
+        timeout_ms: test.timeout,
+        serial: test.serial,
+        should_panic: match &test.kind {
+            TestKind::ShouldPanic { expected } if !cfg!(feature = "std") => {
+                match expected.as_ref().and_then(expected_message) {
+                    Some(message) => Marker::WithMessage(message),
+                    None => Marker::Present,
+                }
+            }
+            _ => Marker::Absent,
+        },
+        fixture: test.receiver.is_some(),
+        generics: suite_params(&test.generics)
+            .expect("const parameters of tests are checked by `analyze`"),
+        ident: test.ident,
+    }
+}
+
+/// Represents the type and const parameters of the trait or a generic test as
+/// [`SuiteParam`]s, which instantiations use to pass their generic arguments to the trait's
+/// handle as types.
+///
+/// Fails if a const parameter's argument can't be represented as a type, i.e. it isn't of a
+/// primitive integer type, `bool`, or `char`.
+fn suite_params<'a>(
+    generics: impl IntoIterator<Item = &'a GenericParam>,
+) -> manyhow::Result<Vec<SuiteParam>> {
+    let mut params = Vec::new();
+    for param in generics {
+        match param {
+            GenericParam::Lifetime(_) => {}
+            GenericParam::Type(param) => params.push(SuiteParam::Type {
+                ident: param.ident.clone(),
+                default: param.default.clone(),
+            }),
+            GenericParam::Const(param) => params.push(SuiteParam::Const {
+                ident: param.ident.clone(),
+                wrapper: const_wrapper(&param.ty)?,
+                default: param.default.clone(),
+            }),
+        }
+    }
+    Ok(params)
+}
+
+/// The type in `tested_trait::__private` that represents arguments of a const parameter of
+/// type `ty` as types, e.g. `ConstU8` for `u8`.
+///
+/// Fails if `ty` isn't a primitive integer type, `bool`, or `char`.
+fn const_wrapper(ty: &Type) -> manyhow::Result<Path> {
+    let wrapper = match ty {
+        Type::Path(ty) if ty.qself.is_none() => ty.path.get_ident(),
+        _ => None,
+    }
+    .and_then(|ty| match ty.to_string().as_str() {
+        "u8" => Some("ConstU8"),
+        "u16" => Some("ConstU16"),
+        "u32" => Some("ConstU32"),
+        "u64" => Some("ConstU64"),
+        "u128" => Some("ConstU128"),
+        "usize" => Some("ConstUsize"),
+        "i8" => Some("ConstI8"),
+        "i16" => Some("ConstI16"),
+        "i32" => Some("ConstI32"),
+        "i64" => Some("ConstI64"),
+        "i128" => Some("ConstI128"),
+        "isize" => Some("ConstIsize"),
+        "bool" => Some("ConstBool"),
+        "char" => Some("ConstChar"),
+        _ => None,
+    })
+    .ok_or_else(|| {
+        error_message!(
+            ty,
+            "#[{MACRO}] only supports const parameters of primitive integer types, `bool`, \
+             and `char`"
+        )
+    })?;
+    let wrapper = Ident::new(wrapper, Span::call_site());
+    Ok(parse_quote! { ::tested_trait::__private::#wrapper })
+}
+
+/// The trait's generic parameters in the forms needed by the items generated alongside it,
+/// which are also generic over the implementer, `__Self`.
+struct TraitGenerics {
+    /// Lifetime parameters, which must precede the implementer and other parameters
+    lifetime_params: Vec<LifetimeParam>,
+    /// Type and const parameters, without their defaults
+    other_params: Vec<GenericParam>,
+    /// The arguments the trait is implemented with, e.g. `<'a, T, N>`
+    args: TokenStream,
+    /// The trait's `where` predicates, in terms of the implementer rather than `Self`
+    predicates: Vec<TokenStream>,
+    /// The types representing the trait's type and const arguments, e.g.
+    /// `(PhantomData<T>, ConstU8<N>,)`, which instantiations pass to the trait's handle
+    suite_args: TokenStream,
+}
+
+impl TraitGenerics {
+    fn new(trait_defn: &ItemTrait, params: &[SuiteParam]) -> Self {
+        let mut lifetime_params = Vec::new();
+        let mut other_params = Vec::new();
+        let mut args = Vec::new();
+        for param in &trait_defn.generics.params {
+            match param {
+                GenericParam::Lifetime(param) => {
+                    lifetime_params.push(param.clone());
+                    args.push(param.lifetime.to_token_stream());
+                }
+                GenericParam::Type(param) => {
+                    other_params.push(GenericParam::Type(TypeParam {
+                        eq_token: None,
+                        default: None,
+                        ..param.clone()
+                    }));
+                    args.push(param.ident.to_token_stream());
+                }
+                GenericParam::Const(param) => {
+                    other_params.push(GenericParam::Const(ConstParam {
+                        eq_token: None,
+                        default: None,
+                        ..param.clone()
+                    }));
+                    args.push(param.ident.to_token_stream());
+                }
+            }
+        }
+        let predicates = (trait_defn.generics.where_clause.iter())
+            .flat_map(|where_clause| &where_clause.predicates)
+            .map(|predicate| super::replace_self(predicate.to_token_stream(), &implementer()))
+            .collect();
+        Self {
+            lifetime_params,
+            other_params,
+            args: quote! { <#(#args),*> },
+            predicates,
+            suite_args: suite_args(params),
+        }
+    }
+}
+
+/// The type representing the arguments of `params`, e.g. `(PhantomData<T>, ConstU8<N>,)`.
+fn suite_args(params: &[SuiteParam]) -> TokenStream {
+    let args = params.iter().map(|param| match param {
+        SuiteParam::Type { ident, .. } => quote! { ::core::marker::PhantomData<#ident> },
+        SuiteParam::Const { ident, wrapper, .. } => quote! { #wrapper<#ident> },
+    });
+    quote! { (#(#args,)*) }
+}
+
+/// A generic test's parameters in the forms needed by the handle's methods, which are also
+/// generic over them: the parameters, the arguments naming them, and the types representing
+/// them passed to the handle after the trait's (if the test has any).
+fn test_generics(test: &AssociatedTest) -> (&[GenericParam], Vec<&Ident>, Option<TokenStream>) {
+    let args = (test.generics.iter())
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => Some(&param.ident),
+            GenericParam::Const(param) => Some(&param.ident),
+            GenericParam::Lifetime(_) => None,
+        })
+        .collect();
+    let suite_args = (!test.generics.is_empty()).then(|| {
+        suite_args(
+            &suite_params(&test.generics)
+                .expect("const parameters of tests are checked by `analyze`"),
+        )
+    });
+    (&test.generics, args, suite_args)
+}
+
+/// The generic parameter standing in for `Self` in items generated outside of the trait.
+fn implementer() -> Ident {
+    Ident::new("__Self", Span::call_site())
+}
+
+/// Generates the trait's tests trait, which holds the test functions so that implementations
+/// can't override them, and the trait's handle, through which instantiations reach them.
+///
+/// The tests trait has the trait as a supertrait and is implemented for all of its
+/// implementers by a blanket impl, so no other impl of it can be written. Its name can't be
+/// derived from a path to the trait, so instantiations reach the handle through the return
+/// type of the trait's accessor (see [`handle_accessor`]). The handle's inherent methods
+/// return the test functions (or optional tests' probes) of the implementation identified by
+/// their `PhantomData` argument.
+fn sealed_tests(
+    trait_defn: &ItemTrait,
+    generics: &TraitGenerics,
+    test_fns: &[TokenStream],
+    handle_methods: &[TokenStream],
+) -> Vec<Item> {
+    let vis = &trait_defn.vis;
+    let trait_name = &trait_defn.ident;
+    let tests_trait = tests_trait_ident(trait_name);
+    let handle = handle_ident(trait_name);
+    let implementer = implementer();
+    let TraitGenerics {
+        lifetime_params,
+        other_params,
+        args,
+        predicates,
+        suite_args: _,
+    } = generics;
+    let params = (trait_defn.generics.params.iter()).map(|param| match param {
+        GenericParam::Type(param) => GenericParam::Type(TypeParam {
+            eq_token: None,
+            default: None,
+            ..param.clone()
+        }),
+        GenericParam::Const(param) => GenericParam::Const(ConstParam {
+            eq_token: None,
+            default: None,
+            ..param.clone()
+        }),
+        GenericParam::Lifetime(_) => param.clone(),
+    });
+    let where_clause = &trait_defn.generics.where_clause;
+    vec![
+        parse_quote! {
+            #[doc(hidden)]
+            #[allow(non_camel_case_types, dead_code)]
+            #vis trait #tests_trait<#(#params),*>: #trait_name #args #where_clause {
+                #(#test_fns)*
+            }
+        },
+        parse_quote! {
+            impl<
+                #(#lifetime_params,)*
+                #implementer: ?::core::marker::Sized + #trait_name #args,
+                #(#other_params),*
+            > #tests_trait #args for #implementer
+            where
+                #(#predicates),*
+            {
+            }
+        },
+        parse_quote! {
+            #[doc(hidden)]
+            #[derive(Default)]
+            #[allow(non_camel_case_types, dead_code)]
+            #vis struct #handle;
+        },
+        parse_quote! {
+            #[allow(dead_code)]
+            impl #handle {
+                #(#handle_methods)*
+            }
+        },
+    ]
+}
+
+/// Generates the blanket impl of a remote definition for all implementations of its
+/// supertraits, including the foreign trait, so that they can be tested with its tests.
+fn remote_impl(trait_defn: &ItemTrait, generics: &TraitGenerics) -> Item {
+    let trait_name = &trait_defn.ident;
+    let implementer = implementer();
+    let supertraits = super::replace_self(trait_defn.supertraits.to_token_stream(), &implementer);
+    let TraitGenerics {
+        lifetime_params,
+        other_params,
+        args,
+        predicates,
+        suite_args: _,
+    } = generics;
+    parse_quote! {
+        impl<
+            #(#lifetime_params,)*
+            #implementer: ?::core::marker::Sized + #supertraits,
+            #(#other_params),*
+        > #trait_name #args for #implementer
+        where
+            #(#predicates),*
+        {
+        }
+    }
+}
+
+fn tests_trait_ident(trait_name: &Ident) -> Ident {
+    Ident::new(
+        &format!("__TestedTraitTests_{trait_name}"),
+        Span::call_site(),
+    )
+}
+
+fn handle_ident(trait_name: &Ident) -> Ident {
+    Ident::new(
+        &format!("__TestedTraitHandle_{trait_name}"),
+        Span::call_site(),
+    )
+}
+
+/// Generates the trait's method returning its handle, through which instantiations reach its
+/// tests.
+///
+/// Instantiations name the method through the implementation of the trait, or, for subtraits
+/// that include its tests, find it on a type parameter bounded by the subtrait, as their suite
+/// macro found the trait through a path in the subtrait's definition. It takes `&self` to keep
+/// the trait dyn-compatible, but is only used for its return type, so overriding it has no
+/// effect.
+fn handle_accessor(trait_defn: &ItemTrait) -> TraitItem {
+    let handle = handle_ident(&trait_defn.ident);
+    let method = HandleAccessorIdent(&trait_defn.ident);
+    parse_quote! {
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        fn #method(&self) -> #handle {
+            #handle
+        }
+    }
+}
+
+/// Generates the trait's method returning the handle of a `#[trait_tests]` module whose
+/// tests it includes, which its instantiations reach them through like a supertrait's.
+///
+/// The handle is found next to the module's trait, which shares the module's name.
+fn tests_module_handle_accessor(module: &Path) -> TraitItem {
+    let mut handle = module.clone();
+    let name = &(module.segments.last())
+        .expect("module `Path`s contain at least one segment")
+        .ident;
+    handle.segments.push(handle_ident(name).into());
+    let method = HandleAccessorIdent(name);
+    parse_quote! {
+        #[doc(hidden)]
+        #[allow(non_snake_case, private_interfaces)]
+        fn #method(&self) -> #handle {
+            #handle
+        }
+    }
+}
+
+/// Generates the handle's method returning a (non-optional) test's function, which is only
+/// callable for implementations satisfying the test's bounds.
+///
+/// Tests taking `self` are returned as a `fn(Implementer)`, which takes the fixture to run
+/// the test against.
+fn test_fn_getter(
+    trait_defn: &ItemTrait,
+    generics: &TraitGenerics,
+    test: &AssociatedTest,
+) -> TokenStream {
+    let trait_name = &trait_defn.ident;
+    let tests_trait = tests_trait_ident(trait_name);
+    let implementer = implementer();
+    let TraitGenerics {
+        lifetime_params,
+        other_params,
+        args,
+        predicates,
+        suite_args,
+    } = generics;
+    let test_bounds = (test.bounds.iter())
+        .flat_map(|bounds| &bounds.predicates)
+        .map(|predicate| super::replace_self(predicate.to_token_stream(), &implementer));
+    let test_fn_ident = AssociatedTestFnIdent(&test.ident);
+    let (test_params, test_args, test_suite_args) = test_generics(test);
+    let test_suite_args = test_suite_args.iter();
+    let test_fn =
+        quote! { <#implementer as #tests_trait #args>::#test_fn_ident::<#(#test_args),*> };
+    let (test_fn_ty, test_fn) = match &test.receiver {
+        Some(receiver) => (quote! { fn(#implementer) }, with_fixture(receiver, test_fn)),
+        None => (quote! { fn() }, test_fn),
+    };
+    quote! {
+        pub fn #test_fn_ident<
+            #(#lifetime_params,)*
+            #implementer,
+            #(#other_params,)*
+            #(#test_params),*
+        >(
+            self,
+            _: ::core::marker::PhantomData<*const #implementer>,
+            _: ::core::marker::PhantomData<(#suite_args, #(#test_suite_args)*)>,
+        ) -> #test_fn_ty
+        where
+            #implementer: #trait_name #args,
+            #(#predicates,)*
+            #(#test_bounds,)*
+        {
+            #test_fn
+        }
+    }
+}
+
+/// Adapts the function of a test taking `self` to take its fixture by value, lending it to
+/// the test if it takes `&self` or `&mut self`.
+fn with_fixture(receiver: &Receiver, test_fn: TokenStream) -> TokenStream {
+    match (&receiver.reference, &receiver.mutability) {
+        (None, _) => test_fn,
+        (Some(_), None) => quote! { |fixture| #test_fn(&fixture) },
+        (Some(_), Some(_)) => quote! { |mut fixture| #test_fn(&mut fixture) },
+    }
+}
+
+/// Generates the trait's `tested_trait_suite()` method, which returns the tests instantiated
+/// for an implementation, and the ID identifying the trait when instantiating them.
+///
+/// Each instantiation implements `tested_trait::__private::SuiteOf<ID, Args, Local>` for the
+/// implementer, where `ID` is a hash unique to the trait's definition, `Args` its generic
+/// arguments, and `Local` a type defined alongside the instantiation (to satisfy the orphan
+/// rules). As an implementation is only instantiated once, the method can infer `Local`.
+///
+/// Returns `None` without the `std` feature.
+fn suite_accessor(trait_defn: &ItemTrait, generics: &TraitGenerics) -> Option<(LitInt, TraitItem)> {
+    if !cfg!(feature = "std") {
+        return None;
+    }
+    let args = &generics.suite_args;
+    let id = super::fnv1a(&super::stable_id(&trait_defn.ident));
+    let id = LitInt::new(&format!("{id}u64"), Span::call_site());
+    let method = parse_quote! {
+        /// Returns the associated tests instantiated for `Self` by `#[test_impl]`, which
+        /// custom harnesses can use to list, filter, and run them individually.
+        fn tested_trait_suite<__TestedTraitSuite>() -> &'static [::tested_trait::TestCase]
+        where
+            Self: ::core::marker::Sized
+                + ::tested_trait::__private::SuiteOf<#id, #args, __TestedTraitSuite>,
+        {
+            <Self as ::tested_trait::__private::SuiteOf<#id, #args, __TestedTraitSuite>>::tests()
+        }
+    };
+    Some((id, method))
+}
+
+/// Generates the body of a `#[proptest]` test, which generates its arguments and runs it for
+/// each case, panicking with the minimal failing input if any fails.
+fn proptest_test(
+    trait_name: &Ident,
+    ident: &Ident,
+    proptest: &Proptest,
+    body: &TokenStream,
+) -> TokenStream {
+    let Proptest {
+        cases,
+        config,
+        args,
+    } = proptest;
+    let config = super::quote_option(config.as_ref());
+    let cases = super::quote_option(cases.as_ref());
+    let pats = args.iter().map(|arg| &arg.pat);
+    let tys = args.iter().map(|arg| &arg.ty).collect::<Vec<_>>();
+    let strategies = args.iter().map(|arg| {
+        let ty = &arg.ty;
+        (arg.strategy.as_ref()).map_or_else(
+            || quote! { ::tested_trait::proptest::arbitrary::any::<#ty>() },
+            ToTokens::to_token_stream,
+        )
+    });
+    let format = (args.iter())
+        .map(|arg| format!("{} = {{:?}}", super::pretty_print(&arg.pat)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let indices = (0..args.len()).map(syn::Index::from);
+    quote! {
+        ::tested_trait::__private::run_proptest::<Self, _>(
+            ::core::stringify!(#trait_name),
+            ::core::stringify!(#ident),
+            #config,
+            #cases,
+            (#(#strategies,)*),
+            |input: &(#(#tys,)*), f: &mut ::core::fmt::Formatter<'_>| {
+                ::core::write!(f, #format, #(input.#indices),*)
+            },
+            |(#(#pats,)*): (#(#tys,)*)| {
+                let (): () = #body;
+                ::core::result::Result::Ok(())
+            },
+        );
+    }
+}
+
+/// Generates the body of a `#[quickcheck]` test, which passes the test to quickcheck as a
+/// function pointer taking its arguments.
+fn quickcheck_test(
+    trait_name: &Ident,
+    ident: &Ident,
+    quickcheck: &Quickcheck,
+    body: &TokenStream,
+) -> TokenStream {
+    let Quickcheck {
+        tests,
+        args,
+        output,
+    } = quickcheck;
+    let tests = super::quote_option(tests.as_ref());
+    let tys = args.iter().map(|arg| &arg.ty);
+    quote! {
+        ::tested_trait::__private::run_quickcheck::<Self, _>(
+            ::core::stringify!(#trait_name),
+            ::core::stringify!(#ident),
+            #tests,
+            (|#(#args),*| -> #output #body) as fn(#(#tys),*) -> #output,
+        );
+    }
+}
+
+/// `#[case(0, 0)]` or `#[case::description(0, 0)]`, which runs a copy of the test with its
+/// parameters bound to the given arguments
+struct Case {
+    attr: Attribute,
+    description: Option<Ident>,
+    args: Punctuated<Expr, Token![,]>,
+}
+
+fn parse_case(attr: &Attribute, params: &[&PatType]) -> manyhow::Result<Case> {
+    let description = match &attr.path().segments.iter().collect::<Vec<_>>()[..] {
+        [_] => None,
+        [_, description] if description.arguments.is_none() => Some(description.ident.clone()),
+        _ => bail!(attr, "expected `#[case(..)]` or `#[case::description(..)]`"),
+    };
+    let args = attr.parse_args_with(Punctuated::parse_terminated)?;
+    if args.len() != params.len() {
+        bail!(
+            attr,
+            "expected {} argument{} for the test's parameters, found {}",
+            params.len(),
+            if params.len() == 1 { "" } else { "s" },
+            args.len()
+        )
+    }
+    Ok(Case {
+        attr: attr.clone(),
+        description,
+        args,
+    })
+}
+
+/// The expected message of a `#[should_panic]` test, if it's a string literal.
+fn expected_message(expected: &Expr) -> Option<LitStr> {
+    match expected {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(message),
+            ..
+        }) => Some(message.clone()),
+        _ => None,
+    }
+}
+
+/// Splits predicates with multiple bounds, e.g. `T: Clone + Debug`, into a predicate per bound.
+fn individual_bounds(where_clause: Option<&WhereClause>) -> Vec<WherePredicate> {
+    (where_clause.iter())
+        .flat_map(|where_clause| &where_clause.predicates)
+        .flat_map(|predicate| match predicate {
+            WherePredicate::Type(predicate) => (predicate.bounds.iter())
+                .map(|bound| {
+                    WherePredicate::Type(PredicateType {
+                        bounds: Punctuated::from_iter([bound.clone()]),
+                        ..predicate.clone()
+                    })
+                })
+                .collect(),
+            predicate => vec![predicate.clone()],
+        })
+        .collect()
+}
+
+/// Generates a probe that determines whether an implementation satisfies an optional test's
+/// bounds, returning the handle's method constructing the probe and the probe's definition.
+///
+/// The probe is a type with a `BOUND` const parameter and an inherent impl for each
+/// `BOUND`: `0` for all of the test's bounds, providing the test function (taking the fixture,
+/// if the test takes `self`), and `1..` for each
+/// individual bound. Each inherent impl has its bounds as `where` clauses, so its method is
+/// only callable on implementations satisfying them -- otherwise, method resolution falls back
+/// to `tested_trait::__private::Unsatisfied`'s methods for `&Probe`.
+fn optional_test_probe(
+    trait_defn: &ItemTrait,
+    generics: &TraitGenerics,
+    test: &AssociatedTest,
+) -> (TokenStream, Vec<Item>) {
+    let vis = &trait_defn.vis;
+    let trait_name = &trait_defn.ident;
+    let tests_trait = tests_trait_ident(trait_name);
+    let probe = Ident::new(
+        &format!("__TestedTrait_{trait_name}_{}", test.ident),
+        Span::call_site(),
+    );
+    let implementer = implementer();
+    let TraitGenerics {
+        lifetime_params,
+        other_params: other_params_with_bounds,
+        args: trait_args,
+        predicates: trait_bounds,
+        suite_args,
+    } = generics;
+
+    let (test_params, test_args, test_suite_args) = test_generics(test);
+    let other_params_with_bounds = (other_params_with_bounds.iter())
+        .chain(test_params)
+        .collect::<Vec<_>>();
+
+    // The probe is generic over the implementer and the trait's and test's parameters.
+    // Lifetimes must precede other parameters, so the implementer is placed after them.
+    let probe_generics = ProbeGenerics::new(trait_defn.generics.params.iter().chain(test_params));
+    let lifetime_args = &probe_generics.lifetime_args;
+    let other_args = &probe_generics.other_args;
+
+    let definition = probe_generics.definition(vis, &probe, &implementer);
+
+    // The inherent impls are written in terms of the implementer rather than `Self`
+    let replace_self =
+        |predicate: &WherePredicate| super::replace_self(predicate.to_token_stream(), &implementer);
+    let test_bounds = (test.bounds.iter())
+        .flat_map(|bounds| &bounds.predicates)
+        .map(replace_self)
+        .collect::<Vec<_>>();
+    let individual_test_bounds = (individual_bounds(test.bounds.as_ref()).iter())
+        .map(replace_self)
+        .collect::<Vec<_>>();
+    let probe_impl = |bound: usize, bounds: &[TokenStream], method: TokenStream| -> Item {
+        parse_quote! {
+            #[allow(dead_code)]
+            impl<#(#lifetime_params,)* #implementer, #(#other_params_with_bounds,)*>
+                #probe<#(#lifetime_args,)* #implementer, #(#other_args,)* #bound>
+            where
+                #implementer: #trait_name #trait_args,
+                #(#trait_bounds,)*
+                #(#bounds,)*
+            {
+                #method
+            }
+        }
+    };
+    let test_fn_ident = AssociatedTestFnIdent(&test.ident);
+    let test_fn = quote! {
+        <#implementer as #tests_trait #trait_args>::#test_fn_ident::<#(#test_args),*>
+    };
+    let test_fn_method = probe_test_fn_method(test, &implementer, test_fn);
+    let mut items = vec![definition, probe_impl(0, &test_bounds, test_fn_method)];
+    items.extend(individual_test_bounds.iter().enumerate().map(|(i, bound)| {
+        probe_impl(
+            i + 1,
+            std::slice::from_ref(bound),
+            quote! {
+                pub fn __tested_trait_satisfied(&self) -> ::core::primitive::bool {
+                    true
+                }
+            },
+        )
+    }));
+
+    let probe_fn_ident = AssociatedTestProbeIdent(&test.ident);
+    let test_suite_args = test_suite_args.iter();
+    let probe_fn = quote! {
+        pub fn #probe_fn_ident<
+            #(#lifetime_params,)*
+            #implementer,
+            #(#other_params_with_bounds,)*
+            const BOUND: ::core::primitive::usize,
+        >(
+            self,
+            _: ::core::marker::PhantomData<*const #implementer>,
+            _: ::core::marker::PhantomData<(
+                #suite_args,
+                #(#test_suite_args,)*
+                ::tested_trait::__private::ConstUsize<BOUND>,
+            )>,
+        ) -> #probe<#(#lifetime_args,)* #implementer, #(#other_args,)* BOUND>
+        where
+            #implementer: #trait_name #trait_args,
+            #(#trait_bounds,)*
+        {
+            #probe(::core::marker::PhantomData)
+        }
+    };
+    (probe_fn, items)
+}
+
+/// The generic parameters of an optional test's probe other than the implementer and
+/// `BOUND`, in the forms needed to define and name it.
+struct ProbeGenerics {
+    lifetime_args: Vec<syn::Lifetime>,
+    /// Type and const parameters, without their bounds
+    other_params: Vec<TokenStream>,
+    other_args: Vec<TokenStream>,
+    /// The types the probe's `PhantomData` holds, so it uses its lifetime and type parameters
+    phantom_types: Vec<TokenStream>,
+}
+
+impl ProbeGenerics {
+    fn new<'a>(params: impl IntoIterator<Item = &'a GenericParam>) -> Self {
+        let mut generics = Self {
+            lifetime_args: Vec::new(),
+            other_params: Vec::new(),
+            other_args: Vec::new(),
+            phantom_types: Vec::new(),
+        };
+        for param in params {
+            match param {
+                GenericParam::Lifetime(param) => {
+                    let lifetime = &param.lifetime;
+                    generics.lifetime_args.push(lifetime.clone());
+                    generics.phantom_types.push(quote! { &#lifetime () });
+                }
+                GenericParam::Type(param) => {
+                    let ident = &param.ident;
+                    generics.other_params.push(quote! { #ident });
+                    generics.other_args.push(quote! { #ident });
+                    generics.phantom_types.push(quote! { #ident });
+                }
+                GenericParam::Const(param) => {
+                    let ConstParam { ident, ty, .. } = param;
+                    generics.other_params.push(quote! { const #ident: #ty });
+                    generics.other_args.push(quote! { #ident });
+                }
+            }
+        }
+        generics
+    }
+
+    /// Defines the probe, which is generic over these parameters.
+    fn definition(&self, vis: &Visibility, probe: &Ident, implementer: &Ident) -> Item {
+        let Self {
+            lifetime_args,
+            other_params,
+            other_args: _,
+            phantom_types,
+        } = self;
+        parse_quote! {
+            #[doc(hidden)]
+            #[allow(non_camel_case_types)]
+            #vis struct #probe<
+                #(#lifetime_args,)*
+                #implementer,
+                #(#other_params,)*
+                const BOUND: ::core::primitive::usize,
+            >(
+                ::core::marker::PhantomData<
+                    fn() -> (*const #implementer, #(#phantom_types,)*)
+                >,
+            );
+        }
+    }
+}
+
+/// The method of an optional test's probe that returns `test_fn`, the test function of
+/// `implementer`, when it satisfies all of the test's bounds.
+fn probe_test_fn_method(
+    test: &AssociatedTest,
+    implementer: &Ident,
+    test_fn: TokenStream,
+) -> TokenStream {
+    if let Some(receiver) = &test.receiver {
+        let test_fn = with_fixture(receiver, test_fn);
+        quote! {
+            pub fn __tested_trait_fixture_test_fn(
+                &self,
+            ) -> ::core::option::Option<fn(#implementer)> {
+                let test_fn: fn(#implementer) = #test_fn;
+                ::core::option::Option::Some(test_fn)
+            }
+        }
+    } else {
+        quote! {
+            pub fn __tested_trait_test_fn(&self) -> ::core::option::Option<fn()> {
+                ::core::option::Option::Some(#test_fn)
+            }
+        }
+    }
+}
+
+fn codegen(ir: Ir) -> TokenStream {
+    let Ir {
+        mut trait_defn,
+        mut new_trait_items,
+        new_items,
+        suite,
+        included,
+        feature,
+    } = ir;
+
+    // Everything generated for the tests is only compiled with the feature enabled, or in the
+    // crate's own unit tests
+    let cfg: Option<Attribute> =
+        (feature.as_ref()).map(|feature| parse_quote! { #[cfg(any(test, feature = #feature))] });
+    if let Some(cfg) = &cfg {
+        for item in &mut new_trait_items {
+            if let TraitItem::Fn(item) = item {
+                item.attrs.insert(0, cfg.clone());
+            }
+        }
+    }
+    trait_defn.items.extend(new_trait_items);
+
+    // The suite macro shares the trait's name so that it can be found through any path that
+    // names the trait. Macros that are visible outside of their crate must be exported from
+    // its root, so only `pub` traits' macros are exported.
+    let vis = &trait_defn.vis;
+    let trait_name = &trait_defn.ident;
+    let macro_name = Ident::new(
+        &format!(
+            "__tested_trait_{}_{:016x}",
+            trait_name.unraw(),
+            super::fnv1a(&super::stable_id(trait_name))
+        ),
+        Span::call_site(),
+    );
+    let export = matches!(vis, Visibility::Public(_)).then(|| {
+        quote! {
+            #[macro_export]
+            #[allow(non_local_definitions)]
+        }
+    });
+    // Without the feature, the suite has no tests, and instantiating it explains how to enable it
+    let disabled_macro = feature.map(|feature| {
+        let message = format!(
+            "the associated tests of `{trait_name}` are only compiled with the `{}` feature \
+             of `{}`, which must be enabled to test its implementations",
+            feature.value(),
+            std::env::var("CARGO_PKG_NAME").unwrap_or_else(|_| String::from("its crate")),
+        );
+        quote! {
+            #[cfg(not(any(test, feature = #feature)))]
+            #[doc(hidden)]
+            #export
+            macro_rules! #macro_name {
+                ($($instantiation:tt)*) => {
+                    ::tested_trait::__private::instantiate_suite! {
+                        { #[disabled(#message)] trait #trait_name {} }
+                        { $($instantiation)* }
+                    }
+                };
+            }
+        }
+    });
+    let new_items = new_items.iter().map(|item| quote! { #cfg #item });
+    // The included suites are only found through the paths naming them here, so their macros
+    // are invoked here in turn to include them before the suite macro is defined
+    let suite_macro = match included.first() {
+        None => quote! {
+            #cfg
+            #[doc(hidden)]
+            #export
+            macro_rules! #macro_name {
+                ($($instantiation:tt)*) => {
+                    ::tested_trait::__private::instantiate_suite! {
+                        { #suite }
+                        { $($instantiation)* }
+                    }
+                };
+            }
+        },
+        Some(first) => {
+            let inclusion = Inclusion {
+                attrs: parse_quote! {
+                    #[doc(hidden)]
+                    #export
+                },
+                macro_name: macro_name.clone(),
+                subtrait: suite,
+                included: included.iter().cloned().collect(),
+            };
+            let first_macro = super::instantiate_suite::bare_path(first);
+            quote! {
+                #cfg
+                #first_macro! { #inclusion }
+            }
+        }
+    };
+    quote! {
+        #trait_defn
+        #(#new_items)*
+
+        #suite_macro
+        #disabled_macro
+
+        #[doc(hidden)]
+        #[allow(unused_imports)]
+        #vis use #macro_name as #trait_name;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::{parse_quote, TraitItem, TraitItemFn};
+
+    use crate::tested_trait::{Args, AssociatedTest, Ast, TestKind};
+
+    use super::{analyze, parse};
+
+    #[test]
+    fn valid_syntax() {
+        parse(
+            quote!(),
+            quote! {
+                trait Foo {
+                    fn foo() -> bool;
+
+                    #[test]
+                    fn foo_is_true() {
+                        assert!(Self::foo());
+                    }
+                }
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn tests_extracted_from_trait() {
+        let test: TraitItemFn = parse_quote! { #[test] fn test() {} };
+        let model = analyze(Ast {
+            args: Args::default(),
+            trait_defn: parse_quote! {
+                trait Foo {
+                    fn foo();
+                    #test
+                    fn bar() {}
+                }
+            },
+        })
+        .unwrap();
+        assert!(!(model.trait_defn.items).contains(&TraitItem::Fn(test.clone())));
+        assert_eq!(
+            [AssociatedTest {
+                kind: TestKind::Standard,
+                ident: test.sig.ident,
+                is_async: false,
+                receiver: None,
+                generics: Vec::new(),
+                bounds: test.sig.generics.where_clause,
+                optional: false,
+                ignore: None,
+                timeout: None,
+                serial: false,
+                property: None,
+                body: test.default.unwrap(),
+            }]
+            .as_slice(),
+            model.tests
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use manyhow::{bail, error_message};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{ParseStream, Parser},
+    parse_quote, token, Item, ItemMod, ItemTrait, Path, Token, TraitItem, TraitItemFn, Visibility,
+};
+
+pub fn trait_tests(args: TokenStream, item: TokenStream) -> manyhow::Result<TokenStream> {
+    let ast = parse(args, item)?;
+    let ir = lower(ast)?;
+    codegen(ir)
+}
+
+const MACRO: &str = "trait_tests";
+
+struct Ast {
+    /// The trait the tests are for
+    trait_: Path,
+    /// The arguments following the trait, which are those of `#[tested_trait]`
+    args: TokenStream,
+    module: ItemMod,
+}
+
+fn parse(args: TokenStream, item: TokenStream) -> manyhow::Result<Ast> {
+    let module: ItemMod = syn::parse2(item).map_err(|err| {
+        error_message!(
+            err.span(),
+            "#[{MACRO}] can only be used to annotate modules"
+        )
+    })?;
+    let (trait_, args) = (|input: ParseStream| {
+        input.parse::<Token![for]>()?;
+        let trait_ = input.parse()?;
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+        Ok((trait_, input.parse()?))
+    })
+    .parse2(args)
+    .map_err(|err| {
+        error_message!(
+            err.span(),
+            "#[{MACRO}] must name the trait the tests are for, as in #[{MACRO}(for Trait)]"
+        )
+    })?;
+    Ok(Ast {
+        trait_,
+        args,
+        module,
+    })
+}
+
+struct Ir {
+    /// The module, without its tests
+    module: ItemMod,
+    /// The trait holding the tests, which shares the module's name
+    tests_trait: ItemTrait,
+    trait_: Path,
+    args: TokenStream,
+}
+
+/// Moves the module's `#[test]` functions into a trait extending the trait they're for, in
+/// which they're associated tests like those in the trait's own definition.
+fn lower(ast: Ast) -> manyhow::Result<Ir> {
+    let Ast {
+        trait_,
+        args,
+        mut module,
+    } = ast;
+    let Some((_, items)) = module.content.take() else {
+        bail!(module, "#[{MACRO}] modules must have a body")
+    };
+    let is_test = |item: &Item| match item {
+        Item::Fn(item) => (item.attrs.iter())
+            .any(|attr| matches!(&attr.meta, syn::Meta::Path(path) if path.is_ident("test"))),
+        _ => false,
+    };
+    let (tests, items): (Vec<_>, Vec<_>) = items.into_iter().partition(is_test);
+    let tests = (tests.into_iter())
+        .map(|test| {
+            let Item::Fn(test) = test else {
+                unreachable!("only functions are tests")
+            };
+            if !matches!(test.vis, Visibility::Inherited) {
+                bail!(test.vis, "associated #[test]s cannot have a visibility")
+            }
+            Ok(TraitItem::Fn(TraitItemFn {
+                attrs: test.attrs,
+                sig: test.sig,
+                default: Some(*test.block),
+                semi_token: None,
+            }))
+        })
+        .collect::<manyhow::Result<Vec<_>>>()?;
+    module.content = Some((token::Brace::default(), items));
+    let ident = &module.ident;
+    let vis = trait_vis(&module.vis);
+    let tests_trait = parse_quote! {
+        #[allow(non_camel_case_types)]
+        #vis trait #ident: #trait_ {
+            #(#tests)*
+        }
+    };
+    Ok(Ir {
+        module,
+        tests_trait,
+        trait_,
+        args,
+    })
+}
+
+/// The visibility of the module's trait, which must be visible wherever the module is.
+fn trait_vis(module_vis: &Visibility) -> Visibility {
+    match module_vis {
+        Visibility::Inherited => parse_quote! { pub(super) },
+        Visibility::Restricted(vis) if vis.path.is_ident("self") => parse_quote! { pub(super) },
+        Visibility::Public(_) => module_vis.clone(),
+        Visibility::Restricted(vis) if vis.path.is_ident("crate") => module_vis.clone(),
+        Visibility::Restricted(_) => parse_quote! { pub(crate) },
+    }
+}
+
+fn codegen(ir: Ir) -> manyhow::Result<TokenStream> {
+    let Ir {
+        module,
+        tests_trait,
+        trait_,
+        args,
+    } = ir;
+    let ItemMod {
+        attrs,
+        vis,
+        unsafety,
+        mod_token,
+        ident,
+        content,
+        ..
+    } = module;
+    let items = content.map(|(_, items)| items).unwrap_or_default();
+    let tests = super::tested_trait::out_of_line_tests(args, tests_trait)?;
+    // Like the trait's own definition, the tests can refer to the items in its scope
+    Ok(quote! {
+        #(#attrs)*
+        #vis #unsafety #mod_token #ident {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #(#items)*
+
+            #tests
+
+            impl<__Self: ?::core::marker::Sized + #trait_> #ident for __Self {}
+        }
+    })
+}
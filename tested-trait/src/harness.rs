@@ -0,0 +1,470 @@
+//! A runner for test targets with `harness = false`, which runs every implementation tested with
+//! [`test_impl`](crate::test_impl) in the target and mimics the standard test harness's command
+//! line interface and output.
+//!
+//! ```toml
+//! [[test]]
+//! name = "conformance"
+//! harness = false
+//! ```
+//!
+//! ```ignore
+//! // tests/conformance.rs
+//! use tested_trait::test_impl;
+//!
+//! #[test_impl]
+//! impl my_crate::Allocator for my_crate::BumpAllocator {
+//!     // ...
+//! }
+//!
+//! fn main() {
+//!     tested_trait::harness::main()
+//! }
+//! ```
+//!
+//! The runner supports the standard harness's most common options: test name filters,
+//! `--exact`, `--skip`, `--ignored`, `--include-ignored`, `--list`, `--format pretty|terse`,
+//! `--quiet`, `--test-threads`, and `--nocapture`. Like the standard harness, it captures the panics
+//! of failing tests to print them with the test, but not their other output.
+
+use core::{cell::RefCell, fmt::Write as _, num::NonZeroUsize};
+use std::{
+    boxed::Box,
+    env, eprintln, format,
+    io::{self, Write},
+    panic, process,
+    string::{String, ToString},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc, Mutex, Once, PoisonError,
+    },
+    thread, thread_local,
+    time::Instant,
+    vec,
+    vec::Vec,
+};
+
+use crate::{
+    report::{self, Status, SuiteReport, TestReport},
+    runner::{panic_message, RunIgnored},
+    Outcome, TestCase,
+};
+
+/// The tests instantiated by a [`test_impl`](crate::test_impl) invocation, which registers them
+/// for the runner.
+#[doc(hidden)]
+pub struct RegisteredSuite {
+    /// `module_path!()` where the implementation is tested
+    pub module_path: &'static str,
+    /// The name of the module that the standard harness's tests would be generated in
+    pub module: &'static str,
+    pub trait_name: &'static str,
+    pub impl_name: &'static str,
+    pub tests: fn() -> &'static [TestCase],
+}
+
+inventory::collect!(RegisteredSuite);
+
+/// Runs the tests selected by the process's command line arguments, then exits the process with
+/// status `101` if any failed (like the standard test harness) or `0` otherwise.
+pub fn main() -> ! {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("error: {err}");
+            process::exit(101);
+        }
+    };
+    match run(&args, registered_tests(), &mut io::stdout()) {
+        Ok(true) => process::exit(0),
+        Ok(false) => process::exit(101),
+        Err(err) => {
+            eprintln!("error: failed printing to stdout: {err}");
+            process::exit(101);
+        }
+    }
+}
+
+// Each flag is a command line option
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Args {
+    filters: Vec<String>,
+    skip: Vec<String>,
+    pub(crate) exact: bool,
+    ignored: RunIgnored,
+    list: bool,
+    nocapture: bool,
+    pub(crate) terse: bool,
+    pub(crate) test_threads: Option<NonZeroUsize>,
+}
+
+impl Args {
+    pub(crate) fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            // Supports both `--option value` and `--option=value`
+            let (option, inline_value) = match arg.split_once('=') {
+                Some((option, value)) if option.starts_with("--") => {
+                    (option.to_string(), Some(value.to_string()))
+                }
+                _ => (arg.clone(), None),
+            };
+            let mut value = |option: &str| {
+                (inline_value.clone().or_else(|| args.next()))
+                    .ok_or_else(|| format!("option `{option}` requires a value"))
+            };
+            match option.as_str() {
+                "--exact" => parsed.exact = true,
+                "--skip" => parsed.skip.push(value("--skip")?),
+                "--ignored" => parsed.ignored = RunIgnored::Only,
+                "--include-ignored" => parsed.ignored = RunIgnored::Yes,
+                "--list" => parsed.list = true,
+                "-q" | "--quiet" => parsed.terse = true,
+                "--format" => match value("--format")?.as_str() {
+                    "pretty" => parsed.terse = false,
+                    "terse" => parsed.terse = true,
+                    format => return Err(format!("unsupported `--format`: `{format}`")),
+                },
+                "--test-threads" => {
+                    let threads = value("--test-threads")?;
+                    parsed.test_threads = Some(threads.parse().map_err(|_| {
+                        format!("`--test-threads` must be a positive integer, found `{threads}`")
+                    })?);
+                }
+                "--nocapture" => parsed.nocapture = true,
+                // Only failing tests' output is captured, and it isn't colored, so these have no
+                // effect
+                "--show-output" | "--test" => {}
+                "--color" => {
+                    value("--color")?;
+                }
+                "-Z" => {
+                    value("-Z")?;
+                }
+                _ if option.starts_with('-') => return Err(format!("unrecognized option `{arg}`")),
+                _ => parsed.filters.push(arg),
+            }
+        }
+        Ok(parsed)
+    }
+
+    pub(crate) fn matches(&self, name: &str) -> bool {
+        let matches = |filter: &String| {
+            if self.exact {
+                name == filter
+            } else {
+                name.contains(filter.as_str())
+            }
+        };
+        (self.filters.is_empty() || self.filters.iter().any(matches))
+            && !self.skip.iter().any(matches)
+    }
+}
+
+/// A registered test with its full name, which matches the standard harness's.
+pub(crate) struct Registered {
+    name: String,
+    suite: &'static RegisteredSuite,
+    test: &'static TestCase,
+}
+
+/// Every registered test, sorted by name.
+pub(crate) fn registered_tests() -> Vec<Registered> {
+    let mut tests = (inventory::iter::<RegisteredSuite>())
+        .flat_map(|suite| {
+            // The standard harness omits the crate's name
+            let path = match suite.module_path.split_once("::") {
+                Some((_crate, path)) => format!("{path}::{}", suite.module),
+                None => String::from(suite.module),
+            };
+            (suite.tests)().iter().map(move |test| Registered {
+                name: format!("{path}::{}", test.name),
+                suite,
+                test,
+            })
+        })
+        .collect::<Vec<_>>();
+    tests.sort_by(|a, b| a.name.cmp(&b.name));
+    tests
+}
+
+/// Runs the tests selected by `args`, writing their progress to `out`, and returns whether none
+/// failed.
+///
+/// Also records the outcomes in the report requested by `TESTED_TRAIT_REPORT`, if any.
+pub(crate) fn run(args: &Args, tests: Vec<Registered>, out: &mut impl Write) -> io::Result<bool> {
+    let total = tests.len();
+    let tests = (tests.into_iter())
+        .filter(|registered| {
+            args.matches(&registered.name)
+                && (args.ignored != RunIgnored::Only || registered.test.ignored)
+        })
+        .collect::<Vec<_>>();
+    let filtered_out = total - tests.len();
+
+    if args.list {
+        for registered in &tests {
+            writeln!(out, "{}: test", registered.name)?;
+        }
+        if !args.terse {
+            writeln!(out)?;
+            writeln!(
+                out,
+                "{} test{}, 0 benchmarks",
+                tests.len(),
+                plural(tests.len())
+            )?;
+        }
+        return Ok(true);
+    }
+
+    let start = Instant::now();
+    writeln!(out)?;
+    writeln!(out, "running {} test{}", tests.len(), plural(tests.len()))?;
+    let mut printed = 0;
+    let mut results = Vec::with_capacity(tests.len());
+    run_parallel(args, &tests, |i, report, output| {
+        let name = &tests[i].name;
+        if args.terse {
+            let status = match &report.status {
+                Status::Passed => '.',
+                Status::Failed { .. } => 'F',
+                Status::Ignored { .. } | Status::Skipped { .. } => 'i',
+            };
+            write!(out, "{status}")?;
+            // Like the standard harness, wraps terse output every 88 tests
+            printed += 1;
+            if printed % 88 == 0 {
+                writeln!(out)?;
+            }
+        } else {
+            match &report.status {
+                Status::Passed => writeln!(out, "test {name} ... ok")?,
+                Status::Failed { .. } => writeln!(out, "test {name} ... FAILED")?,
+                Status::Ignored { reason: None } => writeln!(out, "test {name} ... ignored")?,
+                Status::Ignored {
+                    reason: Some(reason),
+                } => writeln!(out, "test {name} ... ignored, {reason}")?,
+                // The standard harness has no notion of skipped tests
+                Status::Skipped { reason } => writeln!(out, "test {name} ... ignored, {reason}")?,
+            }
+        }
+        out.flush()?;
+        results.push((i, report, output));
+        Ok(())
+    })?;
+    if args.terse && printed % 88 != 0 {
+        writeln!(out)?;
+    }
+
+    let count = |status: fn(&Status) -> bool| {
+        (results.iter())
+            .filter(|(_, report, _)| status(&report.status))
+            .count()
+    };
+    let passed = count(|status| matches!(status, Status::Passed));
+    let ignored = count(|status| matches!(status, Status::Ignored { .. } | Status::Skipped { .. }));
+    let failures = (results.iter())
+        .filter_map(|(i, report, output)| match &report.status {
+            // The captured output includes the panic message, unless it wasn't captured
+            Status::Failed { message } if output.is_empty() => {
+                Some((&tests[*i].name, format!("{message}\n")))
+            }
+            Status::Failed { .. } => Some((&tests[*i].name, output.clone())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if !failures.is_empty() {
+        print_failures(out, &failures)?;
+    }
+    writeln!(out)?;
+    writeln!(
+        out,
+        "test result: {}. {passed} passed; {} failed; {ignored} ignored; 0 measured; \
+         {filtered_out} filtered out; finished in {:.2}s",
+        if failures.is_empty() { "ok" } else { "FAILED" },
+        failures.len(),
+        start.elapsed().as_secs_f64(),
+    )?;
+    writeln!(out)?;
+    let passed = failures.is_empty();
+    record(
+        &tests,
+        results.into_iter().map(|(i, report, _)| (i, report)),
+    );
+    Ok(passed)
+}
+
+/// Prints the output of each failed test, given by name, followed by their names.
+fn print_failures(out: &mut impl Write, failures: &[(&String, String)]) -> io::Result<()> {
+    writeln!(out)?;
+    writeln!(out, "failures:")?;
+    writeln!(out)?;
+    for (name, output) in failures {
+        writeln!(out, "---- {name} stdout ----")?;
+        write!(out, "{output}")?;
+        writeln!(out)?;
+    }
+    writeln!(out)?;
+    writeln!(out, "failures:")?;
+    for (name, _) in failures {
+        writeln!(out, "    {name}")?;
+    }
+    Ok(())
+}
+
+/// Runs `tests` on a pool of threads, reporting their results by index as they finish, along with
+/// their captured output (unless `--nocapture`).
+fn run_parallel(
+    args: &Args,
+    tests: &[Registered],
+    mut report: impl FnMut(usize, TestReport, String) -> io::Result<()>,
+) -> io::Result<()> {
+    let run_ignored = args.ignored != RunIgnored::No;
+    if !args.nocapture {
+        Capture::install();
+    }
+    let workers = (args.test_threads)
+        .or_else(|| thread::available_parallelism().ok())
+        .map_or(1, NonZeroUsize::get)
+        .min(tests.len());
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            let sender = sender.clone();
+            let next = &next;
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(Registered { name, test, .. }) = tests.get(i) else {
+                    break;
+                };
+                let capture = (!args.nocapture).then(|| Capture::new(name));
+                Capture::set(capture.clone());
+                let start = Instant::now();
+                let status = if test.ignored && !run_ignored {
+                    Status::Ignored {
+                        reason: test.ignore_reason,
+                    }
+                } else {
+                    match (test.run)() {
+                        Outcome::Passed => Status::Passed,
+                        Outcome::Failed { message } => Status::Failed { message },
+                        Outcome::Skipped { reason } => Status::Skipped { reason },
+                    }
+                };
+                let report = TestReport {
+                    name: test.name,
+                    status,
+                    duration: start.elapsed(),
+                };
+                Capture::set(None);
+                let output = capture.map(Capture::into_output).unwrap_or_default();
+                let _ = sender.send((i, report, output));
+            });
+        }
+        // Lets the receiver finish once all workers have
+        drop(sender);
+        // Stops reporting if that fails, although the remaining tests still run
+        (receiver.into_iter()).try_for_each(|(i, result, output)| report(i, result, output))
+    })
+}
+
+/// Records the outcomes of `tests`, given by index, grouped by the suite they belong to.
+fn record(tests: &[Registered], results: impl IntoIterator<Item = (usize, TestReport)>) {
+    let mut results = results.into_iter().collect::<Vec<_>>();
+    results.sort_by_key(|(i, _)| *i);
+    let mut suites: Vec<SuiteReport> = Vec::new();
+    for (i, test) in results {
+        let RegisteredSuite {
+            trait_name,
+            impl_name,
+            ..
+        } = tests[i].suite;
+        let suite = (suites.iter_mut())
+            .find(|suite| suite.trait_name == *trait_name && suite.impl_name == *impl_name);
+        match suite {
+            Some(suite) => suite.tests.push(test),
+            None => suites.push(SuiteReport {
+                trait_name: String::from(*trait_name),
+                impl_name: String::from(*impl_name),
+                tests: vec![test],
+            }),
+        }
+    }
+    suites.into_iter().for_each(report::record);
+}
+
+/// The output captured from a test: the panics on the threads running it, which are printed with
+/// its failure, like the standard harness does.
+///
+/// Unlike the standard harness, output printed with `print!` and friends isn't captured, as
+/// stable Rust can't redirect it per thread.
+#[derive(Clone)]
+pub(crate) struct Capture {
+    test: Arc<str>,
+    output: Arc<Mutex<String>>,
+}
+
+thread_local! {
+    /// Where panics on the current thread are printed instead of standard error, if anywhere
+    static CAPTURE: RefCell<Option<Capture>> = const { RefCell::new(None) };
+}
+
+impl Capture {
+    fn new(test: &str) -> Self {
+        Self {
+            test: test.into(),
+            output: Arc::default(),
+        }
+    }
+
+    /// Captures the panics on the current thread, if any, for the threads spawned by tests.
+    pub(crate) fn current() -> Option<Self> {
+        CAPTURE.with(|capture| capture.borrow().clone())
+    }
+
+    /// Prints panics on the current thread to `capture`, or to standard error if `None`.
+    pub(crate) fn set(capture: Option<Self>) {
+        CAPTURE.with(|current| *current.borrow_mut() = capture);
+    }
+
+    fn into_output(self) -> String {
+        let output = self.output.lock().unwrap_or_else(PoisonError::into_inner);
+        output.clone()
+    }
+
+    /// Installs the panic hook that prints panics to the current thread's capture, if any.
+    fn install() {
+        static INSTALL: Once = Once::new();
+        INSTALL.call_once(|| {
+            let default = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                let Some(capture) = Self::current() else {
+                    return default(info);
+                };
+                let mut output = capture
+                    .output
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner);
+                let location = info.location().map(ToString::to_string).unwrap_or_default();
+                let _ = writeln!(
+                    output,
+                    "\nthread '{}' panicked at {location}:\n{}",
+                    capture.test,
+                    panic_message(info.payload()),
+                );
+            }));
+        });
+    }
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
@@ -1,5 +1,6 @@
 #![cfg_attr(not(any(test, doc)), no_std)]
 #![deny(missing_docs, unnameable_test_items)]
+#![allow(clippy::test_attr_in_doctest)]
 
 //! # `tested-trait`
 //!
@@ -41,7 +42,8 @@
 //!
 //! Note the test's `where Self: Default` bound, which it uses to construct an allocator.
 //! Unlike freestanding `#[test]`s, associated tests may have `where` clauses to require additional
-//! functionality for testing purposes.
+//! functionality for testing purposes. Types that can't implement `Default` can instead supply the
+//! value to test with a [fixture](#fixtures).
 //!
 //! Implementers can then use [`test_impl`] to verify that their allocators pass this tests and any
 //! others associated with the trait.
@@ -126,18 +128,20 @@
 //!
 //! - [x] Associating tests with trait definitions
 //! - [x] Running associated tests against non-generic trait implementations and concrete
-//!       instantiations of generic implementations (see [below](#testing-generic-implementations))
+//!   instantiations of generic implementations (see [below](#testing-generic-implementations))
 //! - [x] Most of the standard `#[test]` syntax (see [below](#supported-test-syntax))
-//! - [ ] Understandable names for generated tests: currently, annotating `impl<T> Foo<T> for
-//!       Bar<T>` with [`test_impl`] generates tests named `tested_trait_test_impl_Foo_{N}` --
-//!       ideally they'd be named `tested_trait_test_impl_Foo<{T}>_for_Bar<{T}>`, but converting
-//!       types into valid identifiers is difficult
+//! - [x] A separate `#[test]` for each associated test, so tests can be filtered and fail
+//!   independently (see [below](#generated-tests))
+//! - [x] Understandable, stable names for generated tests, derived from the tested implementation
+//!   (see [below](#generated-tests))
+//! - [x] Listing and running associated tests from custom harnesses (see
+//!   [below](#custom-harnesses))
 //! - [ ] Testing trait implementations for unsized types
-//! - [ ] Support for property-based tests with
-//!       [`quickcheck`](https://docs.rs/quickcheck/latest/quickcheck/) and
-//!       [`proptest`](https://docs.rs/proptest/latest/proptest/)
-//! - [ ] `#![no_std]` support: this crate itself is `#![no-std]`, but the tests it defines require
-//!       [`std::println!`] and [`std::panic::catch_unwind()`]
+//! - [x] Property-based tests with [`proptest`](https://docs.rs/proptest/latest/proptest/) (see
+//!   [below](#property-based-tests))
+//! - [x] Property-based tests with [`quickcheck`](https://docs.rs/quickcheck/latest/quickcheck/)
+//!   (see [below](#property-based-tests))
+//! - [x] `#![no_std]` support (see [below](#no_std-support))
 //!
 //! ### Testing generic implementations
 //!
@@ -196,6 +200,39 @@
 //! }
 //! ```
 //!
+//! Each concrete implementation must be an instantiation of the annotated impl, which is checked at
+//! compile time, so e.g. `Vec<u32>: Wrapper<u32>` is rejected rather than testing another impl.
+//!
+//! Instead of listing concrete implementations, the impl's generic parameters can be assigned lists
+//! of values with `T in [..]`, or `const N in [..]` for const parameters, to test the cartesian
+//! product of their values. Each concrete implementation is tested in a module named after them,
+//! e.g. `Wrapper_for_Padded_u32_16`, and lifetime parameters are instantiated with `'static`:
+//!
+//! ```
+//! # use tested_trait::{tested_trait, test_impl};
+//! # #[tested_trait]
+//! # trait Wrapper<T> {
+//! #     fn wrap(value: T) -> Self;
+//! #     fn unwrap(self) -> T;
+//! #     #[test]
+//! #     fn wrap_then_unwrap() where T: Default + PartialEq + Clone {
+//! #         let value = T::default();
+//! #         assert!(Self::wrap(value.clone()).unwrap() == value);
+//! #     }
+//! # }
+//! struct Padded<T, const N: usize>(T);
+//!
+//! #[test_impl(T in [u32, String], const N in [16, 1024, 4096])]
+//! impl<T, const N: usize> Wrapper<T> for Padded<T, N> {
+//!     fn wrap(value: T) -> Self {
+//!         Padded(value)
+//!     }
+//!     fn unwrap(self) -> T {
+//!         self.0
+//!     }
+//! }
+//! ```
+//!
 //! ### Supported `#[test]` syntax
 //!
 //! Most of the standard `#[test]` syntax is supported:
@@ -229,6 +266,18 @@
 //!     fn should_panic_test3() {
 //!         panic!("ahhhhh")
 //!     }
+//!
+//!     #[test]
+//!     #[ignore]
+//!     fn ignored_test1() {
+//!         panic!("not run by default")
+//!     }
+//!
+//!     #[test]
+//!     #[ignore = "takes too long"]
+//!     fn ignored_test2() {
+//!         loop {}
+//!     }
 //! }
 //!
 //! #[test_impl]
@@ -236,98 +285,2726 @@
 //! impl Foo for () {}
 //! ```
 //!
-//! ## Comparison to `trait_tests`
+//! ### Table-driven tests
 //!
-//! This crate provides similar functionality to the [`trait_tests`] crate, with the following
-//! notable differences:
+//! Tests can take arguments when they have `#[case(..)]`s giving them, like [`rstest`]'s. Each case
+//! is expanded into its own test named `{test}_case_{n}`, or `{test}_case_{n}_{description}` for
+//! cases written `#[case::description(..)]`, so every implementation is checked against the same
+//! table of examples:
 //!
-//! - `trait_tests` defines tests in separate `FooTests` traits,
-//!   while this crate defines them inline in trait definitions
-//! - `trait_tests` allows placing bounds on `FooTests` traits,
-//!   while this crate allows placing them on test functions themselves
-//! - `trait_tests` defines tests as unmarked associated functions,
-//!   while this crate supports the standard `#[test]` syntax and the niceties that come with it
-//! - From my testing, this crate's macros are more hygienic and robust to varying inputs than those
-//!   of `trait_tests`
+//! ```
+//! # use tested_trait::{tested_trait, test_impl};
+//! #[tested_trait]
+//! trait Square {
+//!     fn square(x: u32) -> u32;
 //!
-//! [`trait_tests`]: https://crates.io/crates/trait_tests
-
-/// Compiles functions marked with `#[test]` in the definition of the annotated trait into a test
-/// suite that can be instantiated with [`test_impl`] to verify an implementation of the trait.
-///
-/// See the [crate-level docs](crate) for examples and more details.
-pub use tested_trait_macros::tested_trait;
-
-/// Tests the annotated trait implementation against associated tests defined with [`tested_trait`].
-///
-/// See the [crate-level docs](crate) for examples and more details.
-pub use tested_trait_macros::test_impl;
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    mod object_safety {
-        #[super::tested_trait]
-        trait Foo {}
-        #[super::test_impl]
-        impl Foo for () {}
-        const _: &dyn Foo = &();
-    }
-
-    mod default_bound {
-        #[super::tested_trait]
-        trait Foo {
-            fn must_be_true(&self) -> bool;
-
-            #[test]
-            fn test_simple()
-            where
-                Self: Default,
-            {
-                let foo = Self::default();
-                assert!(foo.must_be_true());
-            }
-        }
-
-        #[super::test_impl]
-        impl Foo for () {
-            fn must_be_true(&self) -> bool {
-                true
-            }
-        }
-    }
-
-    mod should_panic {
-        #[super::tested_trait]
-        trait OptionLike<T> {
-            const NONE: Self;
-            fn unwrap(self) -> T;
-
-            #[test]
-            #[should_panic]
-            fn unwrap_none() {
-                Self::NONE.unwrap();
-            }
-        }
-
-        #[super::test_impl(Option<()>: OptionLike<()>)]
-        impl<T> OptionLike<T> for Option<T> {
-            const NONE: Self = None;
-            fn unwrap(self) -> T {
-                self.unwrap()
-            }
-        }
-    }
-
-    #[test]
-    #[should_panic = "test did not panic as expected"]
-    fn should_panic_doesnt_panic() {
-        #[tested_trait]
-        trait Foo {
-            #[test]
-            #[should_panic]
-            fn doesnt_panic() {}
+//!     #[test]
+//!     #[case(0, 0)]
+//!     #[case::four(4, 16)]
+//!     #[case(9, 81)]
+//!     fn squares(input: u32, expected: u32) {
+//!         assert_eq!(Self::square(input), expected);
+//!     }
+//! }
+//!
+//! #[test_impl]
+//! # #[in_integration_test]
+//! impl Square for () {
+//!     fn square(x: u32) -> u32 {
+//!         x * x
+//!     }
+//! }
+//! ```
+//!
+//! [`rstest`]: https://docs.rs/rstest
+//!
+//! ### Fixtures
+//!
+//! Tests can also take `self`, `&self`, or `&mut self`, for implementations that can't be constructed
+//! through a common bound like `Self: Default`. Each implementation then supplies the value to run
+//! them against with `#[test_impl(fixture = ..)]`, which is evaluated anew for each test:
+//!
+//! ```
+//! # use tested_trait::{tested_trait, test_impl};
+//! #[tested_trait]
+//! trait Stack {
+//!     fn push(&mut self, item: u32);
+//!     fn pop(&mut self) -> Option<u32>;
+//!
+//!     #[test]
+//!     fn pop_returns_last_pushed(&mut self) {
+//!         self.push(1);
+//!         assert_eq!(self.pop(), Some(1));
+//!     }
+//! }
+//!
+//! struct BoundedStack {
+//!     items: Vec<u32>,
+//!     capacity: usize,
+//! }
+//!
+//! impl BoundedStack {
+//!     fn with_capacity(capacity: usize) -> Self {
+//!         Self { items: Vec::new(), capacity }
+//!     }
+//! }
+//!
+//! #[test_impl(fixture = BoundedStack::with_capacity(16))]
+//! # #[in_integration_test]
+//! impl Stack for BoundedStack {
+//!     fn push(&mut self, item: u32) {
+//!         assert!(self.items.len() < self.capacity);
+//!         self.items.push(item);
+//!     }
+//!     fn pop(&mut self) -> Option<u32> {
+//!         self.items.pop()
+//!     }
+//! }
+//! ```
+//!
+//! Tests taking `self` can be run against several named fixtures with
+//! `#[test_impl(fixtures(empty = .., prefilled = ..))]`, in which case each fixture's test is named
+//! after it, e.g. `pop_returns_last_pushed::empty` and `pop_returns_last_pushed::prefilled`.
+//!
+//! ### Generic tests
+//!
+//! Tests can have type and const parameters, and are instantiated with each of the arguments listed
+//! for them with `types(..)`, which can be given as defaults in `#[tested_trait]` and overridden for
+//! each implementation in `#[test_impl]`. Each instantiation is a separate test named after its
+//! arguments, e.g. `round_trip_u8` and `round_trip_Vec_u64`:
+//!
+//! ```
+//! # use tested_trait::{tested_trait, test_impl};
+//! # use std::fmt::Debug;
+//! #[tested_trait(types(U = [u8, String]))]
+//! trait Container {
+//!     type Of<T>;
+//!
+//!     fn wrap<T>(item: T) -> Self::Of<T>;
+//!     fn unwrap<T>(wrapped: Self::Of<T>) -> T;
+//!
+//!     #[test]
+//!     fn round_trip<U: Default + Clone + PartialEq + Debug>() {
+//!         let item = U::default();
+//!         assert_eq!(Self::unwrap(Self::wrap(item.clone())), item);
+//!     }
+//!
+//!     #[test]
+//!     fn arrays<const N: usize>() {
+//!         assert_eq!(Self::unwrap(Self::wrap([0u8; N])), [0; N]);
+//!     }
+//! }
+//!
+//! struct Boxed;
+//!
+//! #[test_impl(types(U = [u8, String, Vec<u64>], N = [0, 16]))]
+//! # #[in_integration_test]
+//! impl Container for Boxed {
+//!     type Of<T> = Box<T>;
+//!
+//!     fn wrap<T>(item: T) -> Box<T> {
+//!         Box::new(item)
+//!     }
+//!     fn unwrap<T>(wrapped: Box<T>) -> T {
+//!         *wrapped
+//!     }
+//! }
+//! ```
+//!
+//! ### Supertraits
+//!
+//! With `#[tested_trait(include_supertraits)]`, a trait's tests include those of its supertraits,
+//! so testing an implementation of the trait also tests the type's implementations of its
+//! supertraits (and of theirs, if they include them too). All of the supertraits must then be
+//! `#[tested_trait]`s, unless the ones to include are listed, e.g. `include_supertraits(Len)`.
+//!
+//! Each trait's tests are grouped under its name: the standard harness runs the supertraits' tests
+//! in modules named after them, e.g. `Stack_for_VecStack::Len::empty_by_default`, and integration
+//! tests report them separately.
+//!
+//! ```
+//! # use tested_trait::{tested_trait, test_impl};
+//! #[tested_trait]
+//! trait Len {
+//!     fn len(&self) -> usize;
+//!
+//!     #[test]
+//!     fn empty_by_default() where Self: Default {
+//!         assert_eq!(Self::default().len(), 0);
+//!     }
+//! }
+//!
+//! #[tested_trait(include_supertraits(Len))]
+//! trait Stack: Len + Default {
+//!     fn push(&mut self, value: u32);
+//!     fn pop(&mut self) -> Option<u32>;
+//!
+//!     #[test]
+//!     fn push_then_pop() {
+//!         let mut stack = Self::default();
+//!         stack.push(1);
+//!         assert_eq!(stack.len(), 1);
+//!         assert_eq!(stack.pop(), Some(1));
+//!     }
+//! }
+//!
+//! #[derive(Default)]
+//! struct VecStack(Vec<u32>);
+//!
+//! impl Len for VecStack {
+//!     fn len(&self) -> usize {
+//!         self.0.len()
+//!     }
+//! }
+//!
+//! // Runs `Len`'s tests as well as `Stack`'s
+//! #[test_impl]
+//! # #[in_integration_test]
+//! impl Stack for VecStack {
+//!     fn push(&mut self, value: u32) {
+//!         self.0.push(value);
+//!     }
+//!     fn pop(&mut self) -> Option<u32> {
+//!         self.0.pop()
+//!     }
+//! }
+//! ```
+//!
+//! ### Out-of-line tests
+//!
+//! Associated tests can also be written outside of the trait's definition, in a module annotated
+//! with `#[trait_tests(for Trait)]`. Its `#[test]` functions are associated tests of the trait,
+//! written just as they would be in its definition, which can use the module's other items and
+//! those in scope where it's defined. Any arguments for the tests' [`tested_trait`] follow the
+//! trait, e.g. `#[trait_tests(for Queue, parallel)]`.
+//!
+//! The module's tests are run alongside the trait's once they're included, either:
+//! - by the trait, with `#[tested_trait(tests = path::to::module)]`, to run them for all of its
+//!   implementations, or
+//! - by an implementation, with `#[test_impl(tests(path::to::module))]`, which lets crates other
+//!   than the trait's contribute tests for it
+//!
+//! Like those of supertraits, they're grouped under the module's name, e.g.
+//! `Queue_for_Deque::capacity_tests::holds_a_hundred`.
+//!
+//! ```
+//! # use tested_trait::{tested_trait, test_impl, trait_tests};
+//! # use std::collections::VecDeque;
+//! #[tested_trait(tests = self::queue_tests)]
+//! trait Queue: Default {
+//!     fn enqueue(&mut self, item: u8);
+//!     fn dequeue(&mut self) -> Option<u8>;
+//! }
+//!
+//! #[trait_tests(for Queue)]
+//! mod queue_tests {
+//!     #[test]
+//!     fn first_in_first_out() {
+//!         let mut queue = Self::default();
+//!         queue.enqueue(1);
+//!         queue.enqueue(2);
+//!         assert_eq!(queue.dequeue(), Some(1));
+//!         assert_eq!(queue.dequeue(), Some(2));
+//!     }
+//! }
+//!
+//! #[trait_tests(for Queue)]
+//! mod capacity_tests {
+//!     #[test]
+//!     fn holds_a_hundred() {
+//!         let mut queue = Self::default();
+//!         for item in 0..100 {
+//!             queue.enqueue(item);
+//!         }
+//!         assert_eq!(queue.dequeue(), Some(0));
+//!     }
+//! }
+//!
+//! #[derive(Default)]
+//! struct Deque(VecDeque<u8>);
+//!
+//! // Runs `queue_tests` and `capacity_tests`
+//! #[test_impl(tests(capacity_tests))]
+//! impl Queue for Deque {
+//!     fn enqueue(&mut self, item: u8) {
+//!         self.0.push_back(item);
+//!     }
+//!     fn dequeue(&mut self) -> Option<u8> {
+//!         self.0.pop_front()
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! ### Foreign traits
+//!
+//! Traits from other crates, such as `std::io::Read`, can't be annotated with `#[tested_trait]`,
+//! but can still have tests in a remote definition: a trait annotated with
+//! `#[tested_trait(remote = path::to::Trait)]`, whose tests are associated tests of the foreign
+//! trait. They can use the foreign trait's items through `Self`, as well as the definition's other
+//! methods, which must have bodies as it's implemented for all of the foreign trait's
+//! implementations. An implementation is then tested with `#[test_impl(with = Definition)]`.
+//!
+//! A remote definition of a generic trait has parameters standing for its arguments, e.g.
+//! `#[tested_trait(remote = From<T>)] trait FromTests<T>`, which are instantiated with the
+//! implementation's.
+//!
+//! ```
+//! # use tested_trait::{tested_trait, test_impl};
+//! use std::io::{self, Read};
+//!
+//! #[tested_trait(remote = std::io::Read)]
+//! trait ReadTests {
+//!     fn read_all(&mut self) -> Vec<u8> {
+//!         let mut buf = Vec::new();
+//!         self.read_to_end(&mut buf).unwrap();
+//!         buf
+//!     }
+//!
+//!     #[test]
+//!     fn reads_until_exhausted(&mut self) {
+//!         assert!(!self.read_all().is_empty());
+//!         assert!(self.read_all().is_empty());
+//!     }
+//! }
+//!
+//! struct Bytes(&'static [u8]);
+//!
+//! #[test_impl(with = ReadTests, fixture = Bytes(b"abc"))]
+//! # #[in_integration_test]
+//! impl Read for Bytes {
+//!     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+//!         self.0.read(buf)
+//!     }
+//! }
+//! ```
+//!
+//! ### Implementations without source
+//!
+//! Implementations that can't be annotated with [`#[test_impl]`](test_impl), such as those
+//! generated by derive or declarative macros, blanket implementations, or implementations for
+//! standard library types in the trait's crate, can be tested with [`test_impls!`] instead. It
+//! takes a list of `Type: Trait` implementations to test, along with the same arguments as
+//! [`test_impl`], e.g. `fixture = ..` or `with = ..`, preceded by `#[in_integration_test]` when
+//! it's used in a function.
+//!
+//! ```
+//! # use tested_trait::{tested_trait, test_impls};
+//! #[tested_trait]
+//! trait Sink {
+//!     fn put(&mut self, byte: u8);
+//!     fn size(&self) -> usize;
+//!
+//!     #[test]
+//!     fn put_grows_size() where Self: Default {
+//!         let mut sink = Self::default();
+//!         sink.put(b'a');
+//!         assert_eq!(sink.size(), 1);
+//!     }
+//! }
+//!
+//! macro_rules! impl_sink {
+//!     ($($ty:ty),*) => {$(
+//!         impl Sink for $ty {
+//!             fn put(&mut self, byte: u8) {
+//!                 self.push(byte.into());
+//!             }
+//!             fn size(&self) -> usize {
+//!                 self.len()
+//!             }
+//!         }
+//!     )*};
+//! }
+//!
+//! impl_sink!(Vec<u8>, String);
+//!
+//! test_impls!(
+//! #   #[in_integration_test]
+//!     Vec<u8>: Sink,
+//!     String: Sink,
+//! );
+//! ```
+//!
+//! ### Optional tests
+//!
+//! Implementations must satisfy the `where` clauses of all associated tests to be tested with
+//! [`test_impl`]. Tests marked `#[optional]` are instead skipped for implementations that don't
+//! satisfy their `where` clauses, printing which bounds were unsatisfied even when the test
+//! harness captures output:
+//!
+//! ```
+//! # use tested_trait::{tested_trait, test_impl};
+//! #[tested_trait]
+//! trait Counter {
+//!     fn new() -> Self;
+//!     fn count(&self) -> usize;
+//!
+//!     #[test]
+//!     fn starts_at_zero() {
+//!         assert_eq!(Self::new().count(), 0);
+//!     }
+//!
+//!     #[test]
+//!     #[optional]
+//!     fn default_matches_new() where Self: Default {
+//!         assert_eq!(Self::default().count(), Self::new().count());
+//!     }
+//! }
+//!
+//! struct Count(usize);
+//!
+//! // Prints "skipping default_matches_new for impl Counter for Count: the implementation does not
+//! // satisfy `Self: Default`"
+//! #[test_impl]
+//! # #[in_integration_test]
+//! impl Counter for Count {
+//!     fn new() -> Self {
+//!         Count(0)
+//!     }
+//!     fn count(&self) -> usize {
+//!         self.0
+//!     }
+//! }
+//! ```
+//!
+//! ### Timeouts
+//!
+//! Tests that may hang on a buggy implementation, like a lock that never releases, can be given a
+//! timeout in milliseconds with `#[timeout = ..]` (or `#[timeout(ms = ..)]`), or a default timeout
+//! for all of a trait's tests with `#[tested_trait(timeout = ..)]`. Tests with a timeout run on a
+//! separate thread and fail if they don't finish in time, though the thread is left running since
+//! threads can't be cancelled:
+//!
+//! ```
+//! # use tested_trait::tested_trait;
+//! #[tested_trait(timeout = 10_000)]
+//! trait Lock {
+//!     fn new() -> Self;
+//!     fn with<R>(&self, f: impl FnOnce() -> R) -> R;
+//!
+//!     #[test]
+//!     #[timeout = 500]
+//!     fn reentrant() {
+//!         let lock = Self::new();
+//!         lock.with(|| lock.with(|| ()));
+//!     }
+//! }
+//! ```
+//!
+//! ### Parallel tests
+//!
+//! Implementations annotated with `#[test_impl] #[in_integration_test]`, e.g. within a doctest or an
+//! integration test's function body, run all of their tests within a single test, one after another
+//! by default. `#[tested_trait(parallel)]` runs them on a pool of threads instead, printing their
+//! results in order once they've all finished. It only affects these implementations, as the
+//! `#[test]`s generated for each associated test and the `harness` feature's runner already run
+//! tests in parallel. Tests that touch global state can opt out with `#[serial]`, which keeps them
+//! from running concurrently with any other associated test:
+//!
+//! ```
+//! # use tested_trait::tested_trait;
+//! #[tested_trait(parallel)]
+//! trait Codec {
+//!     fn encode(input: &[u8]) -> Vec<u8>;
+//!
+//!     #[test]
+//!     fn large_input() {
+//!         let input = vec![0xAB; 1 << 20];
+//!         assert!(!Self::encode(&input).is_empty());
+//!     }
+//!
+//!     #[test]
+//!     #[serial]
+//!     fn respects_env_var() {
+//!         std::env::set_var("CODEC_LEVEL", "9");
+//!         Self::encode(b"hello");
+//!         std::env::remove_var("CODEC_LEVEL");
+//!     }
+//! }
+//! ```
+//!
+//! As with the standard test harness, their `#[ignore]`d tests only run with `--include-ignored`, or
+//! on their own with `--ignored`, which only runs the enclosing test if it's `#[ignore]`d too.
+//!
+//! ### Async tests
+//!
+//! Associated tests can be `async fn`s, whose futures are driven to completion by a minimal built-in
+//! executor. Tests needing a particular runtime can instead specify a function to run their futures
+//! with `#[tested_trait(executor = path::to::function)]`:
+//!
+//! ```
+//! # use core::future::Future;
+//! # use tested_trait::tested_trait;
+//! #[tested_trait(executor = block_on)]
+//! trait Storage {
+//!     async fn put(&self, key: &str, value: Vec<u8>);
+//!     async fn get(&self, key: &str) -> Option<Vec<u8>>;
+//!
+//!     #[test]
+//!     async fn get_after_put()
+//!     where
+//!         Self: Default,
+//!     {
+//!         let storage = Self::default();
+//!         storage.put("key", vec![1, 2, 3]).await;
+//!         assert_eq!(storage.get("key").await, Some(vec![1, 2, 3]));
+//!     }
+//! }
+//!
+//! fn block_on<F: Future>(future: F) -> F::Output {
+//!     tokio::runtime::Builder::new_current_thread()
+//!         .enable_all()
+//!         .build()
+//!         .unwrap()
+//!         .block_on(future)
+//! }
+//! ```
+//!
+//! ### Property-based tests
+//!
+//! With the `proptest` feature, associated tests marked `#[proptest]` are property-based tests run by
+//! [`proptest`](https://docs.rs/proptest/latest/proptest/). Their arguments are generated from their
+//! types' `Arbitrary` implementations or the strategies given with `#[strategy(..)]`, and a failing
+//! input is shrunk to a minimal one, which is reported along with the implementation that failed. The
+//! number of cases can be set for a test with `#[proptest(cases = ..)]`, and for all of the trait's
+//! tests with `#[tested_trait(proptest(cases = ..))]`, which also accepts a
+//! `proptest::test_runner::Config` to run them with as `config = ..`. proptest is re-exported as
+//! `tested_trait::proptest` for its strategies and `prop_assert*!` macros:
+//!
+//! ```
+//! # #[cfg(feature = "proptest")]
+//! # mod doctest {
+//! # use tested_trait::tested_trait;
+//! use tested_trait::proptest::prelude::*;
+//!
+//! #[tested_trait(proptest(cases = 1000))]
+//! trait Codec {
+//!     fn encode(value: &str) -> Vec<u8>;
+//!     fn decode(bytes: &[u8]) -> String;
+//!
+//!     #[test]
+//!     #[proptest]
+//!     fn roundtrip(value: String) {
+//!         prop_assert_eq!(Self::decode(&Self::encode(&value)), value);
+//!     }
+//!
+//!     #[test]
+//!     #[proptest(cases = 64)]
+//!     fn ascii_is_one_byte_per_char(#[strategy("[a-z]{0,16}")] value: String) {
+//!         prop_assert_eq!(Self::encode(&value).len(), value.len());
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! Failing inputs aren't persisted to `proptest-regressions` files unless `config` enables it, as the
+//! tests run in the crates testing implementations rather than the trait's.
+//!
+//! Similarly, with the `quickcheck` feature, associated tests marked `#[quickcheck]` are run by
+//! [`quickcheck`](https://docs.rs/quickcheck/latest/quickcheck/), which generates their arguments
+//! from their types' `Arbitrary` implementations. They return anything quickcheck can test, such as a
+//! `bool` or a `TestResult` that discards inputs the property doesn't apply to, and failures name the
+//! implementation along with quickcheck's shrunk arguments. The number of passing tests required can
+//! be set for a test with `#[quickcheck(tests = ..)]`, and for all of the trait's tests with
+//! `#[tested_trait(quickcheck(tests = ..))]`. quickcheck is re-exported as `tested_trait::quickcheck`:
+//!
+//! ```
+//! # #[cfg(feature = "quickcheck")]
+//! # mod doctest {
+//! # use tested_trait::tested_trait;
+//! use tested_trait::quickcheck::TestResult;
+//!
+//! #[tested_trait]
+//! trait Checked {
+//!     fn checked_div(a: u8, b: u8) -> Option<u8>;
+//!
+//!     #[test]
+//!     #[quickcheck]
+//!     fn quotient_is_at_most_dividend(a: u8, b: u8) -> TestResult {
+//!         if b == 0 {
+//!             return TestResult::discard();
+//!         }
+//!         TestResult::from_bool(Self::checked_div(a, b).unwrap() <= a)
+//!     }
+//!
+//!     #[test]
+//!     #[quickcheck(tests = 10)]
+//!     fn division_by_zero_fails(a: u8) -> bool {
+//!         Self::checked_div(a, 0).is_none()
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! ### Reports
+//!
+//! Associated tests can also write a machine-readable report of each test's status, duration, and
+//! panic message or skip reason to the file named by the `TESTED_TRAIT_REPORT` environment variable:
+//! `JUnit` XML if the file name ends in `.xml`, and JSON otherwise. Each report covers every
+//! implementation tested by the process so far, so separate test binaries should write to separate
+//! files:
+//!
+//! ```sh
+//! TESTED_TRAIT_REPORT=target/tested-trait.xml cargo test --test conformance
+//! ```
+//!
+//! This covers the `#[test]`s generated for each associated test, integration tests, and the
+//! `harness` feature's runner. The standard test harness doesn't run the `#[test]`s of `#[ignore]`d
+//! tests unless asked to, so they're missing from the report.
+//!
+//! ### Generated tests
+//!
+//! [`test_impl`] generates a module for each tested implementation containing one `#[test]` per
+//! associated test, named after the associated test. The module is named after the trait and the
+//! implementing type, so test names stay the same across builds. For example, testing
+//! `impl Allocator for BadAllocator<1024>` generates a test named
+//! `Allocator_for_BadAllocator_1024::alloc_respects_alignment`, which can be run on its own with
+//! `cargo test alloc_respects_alignment`. Implementations whose types can't be spelled out as an
+//! identifier, e.g. because they contain string literals, get a module named after the trait and a
+//! hash of the implementation instead. Implementations spelled out the same, like
+//! `Pair<(u8, u16)>` and `Pair<u8, u16>`, are told apart by appending `_2`, `_3`, etc. to the later
+//! ones' names.
+//!
+//! The module can also be named explicitly, as long as [`test_impl`] instantiates a single concrete
+//! implementation:
+//!
+//! ```
+//! # use tested_trait::{tested_trait, test_impl};
+//! #[tested_trait]
+//! trait Allocator {
+//!     // ...
+//! }
+//!
+//! #[test_impl(name = "bad_alloc_1k")]
+//! impl Allocator for BadAllocator<1024> {
+//!     // ...
+//! }
+//! # struct BadAllocator<const SIZE: usize>;
+//! ```
+//!
+//! ### Feature-gated tests
+//!
+//! Associated tests are compiled into the trait's crate, and so into every crate using the trait.
+//! To keep them out of normal builds, they can be gated behind a feature of the trait's crate with
+//! `#[tested_trait(feature = "...")]`, which implementers then enable to test their implementations
+//! (e.g. as a dev-dependency). The tests are always compiled in the crate's own unit tests:
+//!
+//! ```
+//! # use tested_trait::tested_trait;
+//! #[tested_trait(feature = "conformance-tests")]
+//! pub trait Storage {
+//!     // ...
+//! }
+//! ```
+//!
+//! ```toml
+//! # In the trait's Cargo.toml
+//! [features]
+//! conformance-tests = []
+//!
+//! # In implementers' Cargo.toml
+//! [dev-dependencies]
+//! storage = { version = "1.0", features = ["conformance-tests"] }
+//! ```
+//!
+//! Without the feature, `#[test_impl]` still compiles in normal builds, but fails to compile in
+//! test builds, explaining which feature to enable.
+//!
+//! ### Custom harnesses
+//!
+//! With the `std` feature, [`tested_trait`] also adds a `tested_trait_suite()` method to the trait,
+//! returning the [`TestCase`]s instantiated by [`test_impl`] for an implementation. Custom
+//! harnesses can use them to list, filter, and run associated tests individually:
+//!
+//! ```
+//! # use tested_trait::{tested_trait, test_impl, Outcome};
+//! #[tested_trait]
+//! trait Stack {
+//!     fn push(&mut self, value: u32);
+//!     fn pop(&mut self) -> Option<u32>;
+//!
+//!     #[test]
+//!     fn pop_returns_last_pushed() where Self: Default {
+//!         let mut stack = Self::default();
+//!         stack.push(1);
+//!         stack.push(2);
+//!         assert_eq!(stack.pop(), Some(2));
+//!     }
+//! }
+//!
+//! #[test_impl]
+//! impl Stack for Vec<u32> {
+//!     fn push(&mut self, value: u32) {
+//!         Vec::push(self, value)
+//!     }
+//!     fn pop(&mut self) -> Option<u32> {
+//!         Vec::pop(self)
+//!     }
+//! }
+//!
+//! for case in <Vec<u32> as Stack>::tested_trait_suite() {
+//!     if !case.ignored {
+//!         assert_eq!((case.run)(), Outcome::Passed, "{} failed", case.name);
+//!     }
+//! }
+//! ```
+//!
+//! The `harness` feature provides such a harness: `harness::main()` runs every implementation
+//! tested in a test target with `harness = false`, accepting the standard harness's usual options
+//! (test name filters, `--ignored`, `--list`, `--format terse`, ...) and printing the same output.
+//! This lets associated tests run alongside other tests in targets that can't use the standard
+//! harness:
+//!
+//! ```toml
+//! [[test]]
+//! name = "conformance"
+//! harness = false
+//! ```
+//!
+//! ```no_run
+//! # mod my_crate {
+//! #     #[tested_trait::tested_trait]
+//! #     pub trait Allocator {}
+//! #     pub struct BumpAllocator;
+//! # }
+//! # use tested_trait::test_impl;
+//! // tests/conformance.rs
+//! #[test_impl]
+//! impl my_crate::Allocator for my_crate::BumpAllocator {
+//!     // ...
+//! }
+//!
+//! # #[cfg(feature = "harness")]
+//! fn main() {
+//!     tested_trait::harness::main()
+//! }
+//! # #[cfg(not(feature = "harness"))]
+//! # fn main() {}
+//! ```
+//!
+//! ### `no_std` support
+//!
+//! Traits in `#![no_std]` crates can have associated tests too. Without the default `std` feature,
+//! the code generated by [`tested_trait`] and [`test_impl`] only depends on `core`, so it compiles
+//! in `no_std` libraries while their tests still run on the host as usual. Since panics can't be
+//! caught without `std`:
+//!
+//! - `#[should_panic]` tests are checked by the test harness, so their expected messages must be
+//!   string literals and they can't be run `#[in_integration_test]`
+//! - Tests run `#[in_integration_test]` stop at the first failure, and report their progress to a
+//!   [`Reporter`] set with [`set_reporter()`] rather than printing it
+//! - Timeouts have no effect, and `async` tests are polled until they're ready rather than woken
+//!
+//! ```
+//! # use tested_trait::Reporter;
+//! struct SerialReporter;
+//!
+//! impl Reporter for SerialReporter {
+//!     fn passed(&self, impl_name: &str, test: &str) {
+//!         // e.g. write "test {test} for impl {impl_name} ... ok" to a serial port
+//!     }
+//! }
+//!
+//! tested_trait::set_reporter(&SerialReporter).unwrap();
+//! ```
+//!
+//! ## Comparison to `trait_tests`
+//!
+//! This crate provides similar functionality to the [`trait_tests`] crate, with the following
+//! notable differences:
+//!
+//! - `trait_tests` defines tests in separate `FooTests` traits,
+//!   while this crate defines them inline in trait definitions (or in `#[trait_tests]` modules)
+//! - `trait_tests` allows placing bounds on `FooTests` traits,
+//!   while this crate allows placing them on test functions themselves
+//! - `trait_tests` defines tests as unmarked associated functions,
+//!   while this crate supports the standard `#[test]` syntax and the niceties that come with it
+//! - From my testing, this crate's macros are more hygienic and robust to varying inputs than those
+//!   of `trait_tests`
+//!
+//! [`trait_tests`]: https://crates.io/crates/trait_tests
+
+/// Compiles functions marked with `#[test]` in the definition of the annotated trait into a test
+/// suite that can be instantiated with [`test_impl`] to verify an implementation of the trait.
+///
+/// See the [crate-level docs](crate) for examples and more details.
+pub use tested_trait_macros::tested_trait;
+
+/// Tests the annotated trait implementation against associated tests defined with [`tested_trait`].
+///
+/// See the [crate-level docs](crate) for examples and more details.
+pub use tested_trait_macros::test_impl;
+
+/// Tests a list of `Type: Trait` implementations against associated tests defined with
+/// [`tested_trait`], like [`test_impl`] but without needing the implementations' source.
+///
+/// See the [crate-level docs](crate) for examples and more details.
+pub use tested_trait_macros::test_impls;
+
+/// Compiles functions marked with `#[test]` in the annotated module into associated tests of the
+/// trait it names, e.g. `#[trait_tests(for Allocator)]`, which are run by [`test_impl`] when
+/// included by the trait or by the implementation.
+///
+/// See the [crate-level docs](crate) for examples and more details.
+pub use tested_trait_macros::trait_tests;
+
+#[cfg(test)]
+extern crate self as tested_trait;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "harness")]
+pub mod harness;
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+mod property;
+#[cfg(feature = "std")]
+mod report;
+mod reporter;
+mod runner;
+#[cfg(feature = "std")]
+mod test_case;
+
+pub use reporter::{set_reporter, Reporter, SetReporterError};
+#[cfg(feature = "std")]
+pub use test_case::{Outcome, TestCase, TestKind};
+
+/// The version of [`proptest`](https://docs.rs/proptest) used to run `#[proptest]` tests, whose
+/// strategies and assertion macros they can use.
+#[cfg(feature = "proptest")]
+pub use proptest;
+
+/// The version of [`quickcheck`](https://docs.rs/quickcheck) used to run `#[quickcheck]` tests,
+/// whose `Arbitrary` and `TestResult` types they can use.
+#[cfg(feature = "quickcheck")]
+pub use quickcheck;
+
+#[doc(hidden)]
+pub mod __private {
+    use core::marker::PhantomData;
+
+    pub use tested_trait_macros::instantiate_suite;
+
+    pub use crate::runner::{
+        block_on, run_inline, run_locked, run_or_skip, select_optional, Ignore, Skipped, Test,
+    };
+    #[cfg(feature = "std")]
+    pub use crate::runner::{run_case, run_reported, run_suite, run_with_timeout};
+    #[cfg(feature = "std")]
+    pub use std::{panic::catch_unwind, string::String};
+
+    #[cfg(feature = "proptest")]
+    pub use crate::property::run_proptest;
+    #[cfg(feature = "quickcheck")]
+    pub use crate::property::run_quickcheck;
+
+    #[cfg(feature = "harness")]
+    pub use crate::harness::RegisteredSuite;
+    #[cfg(feature = "harness")]
+    pub use inventory;
+
+    /// The tests instantiated for an implementation of the trait identified by `ID` and `Args`,
+    /// which hold its hash and generic arguments. `Local` is a type local to the implementation's
+    /// test site, which satisfies the orphan rules and is inferred by the trait's accessor.
+    #[cfg(feature = "std")]
+    pub trait SuiteOf<const ID: u64, Args: ?Sized, Local> {
+        fn tests() -> &'static [crate::TestCase];
+    }
+
+    macro_rules! const_args {
+        ($($name:ident: $ty:ty),* $(,)?) => {
+            $(
+                /// Represents a trait's const generic argument as a type.
+                pub struct $name<const N: $ty>;
+            )*
+        };
+    }
+
+    const_args! {
+        ConstU8: u8, ConstU16: u16, ConstU32: u32, ConstU64: u64, ConstU128: u128, ConstUsize: usize,
+        ConstI8: i8, ConstI16: i16, ConstI32: i32, ConstI64: i64, ConstI128: i128, ConstIsize: isize,
+        ConstBool: bool, ConstChar: char,
+    }
+
+    /// Returns the handle of a trait, given its `__tested_trait_handle_*` accessor for the
+    /// implementer `T`.
+    pub fn handle<T: ?Sized, Handle: Default>(
+        _implementer: PhantomData<*const T>,
+        _accessor: fn(&T) -> Handle,
+    ) -> Handle {
+        Handle::default()
+    }
+
+    /// Returns the function of an optional test taking its fixture, which `Unsatisfied`'s
+    /// fallback leaves generic, for the implementer `T`.
+    #[must_use]
+    pub fn fixture_test_fn<T>(
+        _implementer: PhantomData<*const T>,
+        test_fn: Option<fn(T)>,
+    ) -> Option<fn(T)> {
+        test_fn
+    }
+
+    /// Fallbacks for the methods of an optional test's probe, which are only available when the
+    /// implementation satisfies the test's bounds.
+    pub trait Unsatisfied {
+        fn __tested_trait_test_fn(&self) -> Option<fn()> {
+            None
+        }
+
+        fn __tested_trait_fixture_test_fn<T>(&self) -> Option<fn(T)> {
+            None
+        }
+
+        fn __tested_trait_satisfied(&self) -> bool {
+            false
+        }
+    }
+
+    impl<T> Unsatisfied for &T {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The names of `suite`'s tests, in order.
+    fn names(suite: &[TestCase]) -> Vec<&'static str> {
+        suite.iter().map(|case| case.name).collect()
+    }
+
+    /// Runs all of `suite`'s tests, asserting that they pass.
+    fn assert_passes(suite: &[TestCase]) {
+        for case in suite {
+            assert_eq!((case.run)(), Outcome::Passed, "{} failed", case.name);
+        }
+    }
+
+    mod object_safety {
+        #[super::tested_trait]
+        trait Foo {}
+        #[super::test_impl]
+        impl Foo for () {}
+        const _: &dyn Foo = &();
+    }
+
+    mod value_namespace {
+        // The trait's name is left free for values
+        #[allow(non_upper_case_globals)]
+        const Foo: () = ();
+
+        #[super::tested_trait]
+        trait Foo {
+            #[test]
+            fn foo() {}
+        }
+
+        #[super::test_impl]
+        impl Foo for () {}
+
+        #[test]
+        fn value() {
+            let () = Foo;
+        }
+    }
+
+    mod identical_traits {
+        mod a {
+            #[crate::tested_trait]
+            pub trait Foo {
+                #[test]
+                fn foo() {}
+            }
+        }
+
+        mod b {
+            #[crate::tested_trait]
+            pub trait Foo {
+                #[test]
+                fn foo() {}
+            }
+        }
+
+        #[crate::test_impl(name = "a_foo")]
+        impl a::Foo for () {}
+
+        #[crate::test_impl(name = "b_foo")]
+        impl b::Foo for () {}
+
+        #[test]
+        fn suites_are_distinct() {
+            // Each accessor only finds its own trait's instantiation
+            assert_eq!(<() as a::Foo>::tested_trait_suite().len(), 1);
+            assert_eq!(<() as b::Foo>::tested_trait_suite().len(), 1);
+        }
+    }
+
+    mod default_bound {
+        #[super::tested_trait]
+        trait Foo {
+            fn must_be_true(&self) -> bool;
+
+            #[test]
+            fn test_simple()
+            where
+                Self: Default,
+            {
+                let foo = Self::default();
+                assert!(foo.must_be_true());
+            }
+        }
+
+        #[super::test_impl]
+        impl Foo for () {
+            fn must_be_true(&self) -> bool {
+                true
+            }
+        }
+    }
+
+    mod feature_gated {
+        // The feature is never enabled, but the crate's own unit tests are still compiled
+        #![allow(unexpected_cfgs)]
+
+        #[super::tested_trait(feature = "conformance-tests")]
+        trait Foo {
+            #[test]
+            fn foo() {}
+        }
+
+        #[super::test_impl]
+        impl Foo for () {}
+    }
+
+    mod should_panic {
+        #[super::tested_trait]
+        trait OptionLike<T> {
+            const NONE: Self;
+            fn unwrap(self) -> T;
+
+            #[test]
+            #[should_panic]
+            fn unwrap_none() {
+                Self::NONE.unwrap();
+            }
+        }
+
+        #[super::test_impl(Option<()>: OptionLike<()>)]
+        impl<T> OptionLike<T> for Option<T> {
+            const NONE: Self = None;
+            fn unwrap(self) -> T {
+                self.unwrap()
+            }
+        }
+    }
+
+    mod module_names {
+        #[super::tested_trait]
+        trait Foo<T> {
+            #[test]
+            fn test() {}
+        }
+
+        struct Bar<T, const N: usize>(T);
+
+        #[super::test_impl(Bar<u8, 1>: Foo<u8>, Bar<u16, 2>: Foo<u16>)]
+        impl<T, const N: usize> Foo<T> for Bar<T, N> {}
+
+        #[super::test_impl(name = "custom_name")]
+        impl Foo<()> for () {}
+
+        // Modules are named after the trait and type with their generic arguments, unless
+        // overridden by `name`
+        #[allow(unused_imports)]
+        use {custom_name as _, Foo_u16_for_Bar_u16_2 as _, Foo_u8_for_Bar_u8_1 as _};
+    }
+
+    mod colliding_module_names {
+        #[super::tested_trait]
+        trait Foo {
+            #[test]
+            fn test() {}
+        }
+
+        struct Pair<T, U = ()>(T, U);
+
+        #[super::test_impl(Pair<(u8, u16)>: Foo, Pair<u8, u16>: Foo)]
+        impl<T, U> Foo for Pair<T, U> {}
+
+        // Both implementations are spelled out `Foo_for_Pair_u8_u16`
+        #[allow(unused_imports)]
+        use {Foo_for_Pair_u8_u16 as _, Foo_for_Pair_u8_u16_2 as _};
+    }
+
+    mod matrix {
+        use super::{assert_passes, names};
+
+        #[crate::tested_trait]
+        trait Buffer<T> {
+            fn capacity(&self) -> usize;
+
+            #[test]
+            fn has_capacity(&self) {
+                assert!(self.capacity() > 0);
+            }
+        }
+
+        struct Ring<T, const SIZE: usize>([Option<T>; SIZE]);
+
+        impl<T: Copy, const SIZE: usize> Ring<T, SIZE> {
+            fn new() -> Self {
+                Ring([None; SIZE])
+            }
+        }
+
+        #[crate::test_impl(T in [u8, bool], const SIZE in [1, 16], fixture = Ring::new())]
+        impl<T: Copy, const SIZE: usize> Buffer<T> for Ring<T, SIZE> {
+            fn capacity(&self) -> usize {
+                self.0.len()
+            }
+        }
+
+        #[crate::test_impl(T in [u8], fixture = &[0])]
+        impl<'a, T: 'a> Buffer<T> for &'a [T] {
+            fn capacity(&self) -> usize {
+                self.len()
+            }
+        }
+
+        // Each combination of the generic parameters' values gets its own module, named after them
+        #[allow(unused_imports)]
+        use {
+            Buffer_for_Ring_bool_1 as _, Buffer_for_Ring_bool_16 as _, Buffer_for_Ring_u8_1 as _,
+            Buffer_for_Ring_u8_16 as _, Buffer_for_ref_slice_u8 as _,
+        };
+
+        #[test]
+        fn instantiates_cartesian_product() {
+            let suite = <Ring<bool, 16> as Buffer<bool>>::tested_trait_suite();
+            assert_passes(suite);
+            assert_eq!(names(suite), ["has_capacity"]);
+            let suite = <&'static [u8] as Buffer<u8>>::tested_trait_suite();
+            assert_passes(suite);
+            assert_eq!(names(suite), ["has_capacity"]);
+        }
+    }
+
+    mod ignore {
+        #[super::tested_trait]
+        trait Foo {
+            #[test]
+            #[ignore = "ignored tests shouldn't run by default"]
+            fn ignored() {
+                panic!("ignored tests shouldn't run by default")
+            }
+        }
+
+        #[super::test_impl]
+        impl Foo for () {}
+    }
+
+    mod timeout {
+        #[super::tested_trait(timeout = 60_000)]
+        trait Foo {
+            #[test]
+            fn default_timeout() {}
+
+            #[test]
+            #[timeout = 60_000]
+            fn own_timeout() {}
+
+            #[test]
+            #[timeout(ms = 60_000)]
+            #[should_panic = "panicked on the watchdog thread"]
+            fn panics() {
+                panic!("panicked on the watchdog thread")
+            }
+        }
+
+        #[super::test_impl]
+        impl Foo for () {}
+    }
+
+    mod async_tests {
+        use core::{
+            future::Future,
+            pin::Pin,
+            sync::atomic::{AtomicUsize, Ordering},
+            task::{Context, Poll},
+        };
+
+        /// Yields to the executor once before completing
+        struct YieldNow(bool);
+
+        impl Future for YieldNow {
+            type Output = ();
+
+            fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+                if self.0 {
+                    Poll::Ready(())
+                } else {
+                    self.0 = true;
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            }
+        }
+
+        #[super::tested_trait]
+        trait AsyncGet {
+            async fn get(&self) -> usize;
+
+            #[test]
+            async fn get_default() -> Result<(), String>
+            where
+                Self: Default,
+            {
+                YieldNow(false).await;
+                match Self::default().get().await {
+                    0 => Ok(()),
+                    n => Err(format!("expected 0, got {n}")),
+                }
+            }
+
+            #[test]
+            #[should_panic = "expected panic"]
+            async fn panics() {
+                YieldNow(false).await;
+                panic!("expected panic")
+            }
+        }
+
+        #[super::test_impl]
+        impl AsyncGet for usize {
+            async fn get(&self) -> usize {
+                *self
+            }
+        }
+
+        static EXECUTED: AtomicUsize = AtomicUsize::new(0);
+
+        fn counting_executor<F: Future>(future: F) -> F::Output {
+            EXECUTED.fetch_add(1, Ordering::SeqCst);
+            crate::__private::block_on(future)
+        }
+
+        #[super::tested_trait(executor = counting_executor)]
+        trait CustomExecutor {
+            #[test]
+            async fn uses_executor() {
+                assert!(EXECUTED.load(Ordering::SeqCst) > 0);
+            }
+        }
+
+        #[super::test_impl]
+        impl CustomExecutor for () {}
+    }
+
+    mod separate_tests {
+        mod defn {
+            #[crate::tested_trait]
+            pub trait Stack {
+                fn push(&mut self, value: u32);
+                fn pop(&mut self) -> Option<u32>;
+
+                #[test]
+                fn pop_empty()
+                where
+                    Self: Default,
+                {
+                    assert_eq!(Self::default().pop(), None);
+                }
+
+                #[test]
+                fn push_then_pop()
+                where
+                    Self: Default,
+                {
+                    let mut stack = Self::default();
+                    stack.push(1);
+                    stack.push(2);
+                    assert_eq!(stack.pop(), Some(2));
+                    assert_eq!(stack.pop(), Some(1));
+                }
+            }
+        }
+
+        use defn::Stack;
+
+        #[crate::test_impl]
+        impl Stack for Vec<u32> {
+            fn push(&mut self, value: u32) {
+                self.push(value);
+            }
+            fn pop(&mut self) -> Option<u32> {
+                self.pop()
+            }
+        }
+    }
+
+    mod optional {
+        use core::fmt::Debug;
+
+        #[crate::tested_trait]
+        trait Buffer<'a, T: Copy, const N: usize>
+        where
+            Self: Sized,
+        {
+            fn new(items: &'a [T; N]) -> Self;
+            fn get(&self, i: usize) -> T;
+
+            #[test]
+            #[optional]
+            fn get_default()
+            where
+                T: Default + PartialEq + Debug + 'a,
+            {
+                let items = &*std::boxed::Box::leak(std::boxed::Box::new([T::default(); N]));
+                assert_eq!(Self::new(items).get(0), T::default());
+            }
+
+            #[test]
+            #[optional]
+            fn debug()
+            where
+                Self: Debug,
+                T: Default + 'a,
+            {
+                let items = &*std::boxed::Box::leak(std::boxed::Box::new([T::default(); N]));
+                let _ = std::format!("{:?}", Self::new(items));
+            }
+        }
+
+        struct Slice<'a, T>(&'a [T]);
+
+        #[crate::test_impl(Slice<'static, u8>: Buffer<'static, u8, 4>)]
+        impl<'a, T: Copy, const N: usize> Buffer<'a, T, N> for Slice<'a, T> {
+            fn new(items: &'a [T; N]) -> Self {
+                Self(items)
+            }
+            fn get(&self, i: usize) -> T {
+                self.0[i]
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic = "1 of 3 tests failed for impl Foo for Bar: satisfied"]
+    fn optional_tests_skipped() {
+        #[tested_trait]
+        trait Foo {
+            #[test]
+            #[optional]
+            fn satisfied()
+            where
+                Self: Default,
+            {
+                panic!("ran as expected")
+            }
+
+            #[test]
+            #[optional]
+            fn unsatisfied()
+            where
+                Self: Default + Clone,
+            {
+                panic!("should have been skipped")
+            }
+
+            #[test]
+            fn required() {}
+        }
+
+        #[derive(Default)]
+        struct Bar;
+
+        #[test_impl]
+        #[in_integration_test]
+        impl Foo for Bar {}
+    }
+
+    mod cases {
+        use super::{assert_passes, names};
+
+        #[crate::tested_trait]
+        trait Square {
+            fn square(x: u32) -> u32;
+
+            #[test]
+            #[case(0, 0)]
+            #[case::four(4, 16)]
+            #[case(u16::MAX.into(), 4_294_836_225)]
+            fn squares(x: u32, expected: u32) {
+                assert_eq!(Self::square(x), expected);
+            }
+
+            #[test]
+            #[case(2)]
+            fn mutable(mut x: u32) -> Result<(), String> {
+                x *= 2;
+                (Self::square(x) == 16)
+                    .then_some(())
+                    .ok_or(format!("{x} squared isn't 16"))
+            }
+        }
+
+        struct Multiply;
+
+        #[crate::test_impl]
+        impl Square for Multiply {
+            fn square(x: u32) -> u32 {
+                x * x
+            }
+        }
+
+        #[test]
+        fn expanded_into_tests() {
+            let suite = <Multiply as Square>::tested_trait_suite();
+            assert_eq!(
+                names(suite),
+                [
+                    "squares_case_1",
+                    "squares_case_2_four",
+                    "squares_case_3",
+                    "mutable_case_1"
+                ]
+            );
+            assert_passes(suite);
+        }
+    }
+
+    mod fixtures {
+        use core::fmt::Debug;
+
+        use super::{assert_passes, names};
+        use crate::Outcome;
+
+        #[crate::tested_trait]
+        trait Stack {
+            fn push(&mut self, item: u32);
+            fn pop(&mut self) -> Option<u32>;
+            fn len(&self) -> usize;
+
+            #[test]
+            fn push_then_pop(&mut self) {
+                self.push(1);
+                assert_eq!(self.pop(), Some(1));
+            }
+
+            #[test]
+            #[case(1)]
+            #[case::max(u32::MAX)]
+            fn push_grows(mut self, item: u32) {
+                let len = self.len();
+                self.push(item);
+                assert_eq!(self.len(), len + 1);
+            }
+
+            #[test]
+            #[optional]
+            fn clone_is_equal(&self)
+            where
+                Self: Clone + PartialEq + Debug,
+            {
+                assert_eq!(&self.clone(), self);
+            }
+
+            #[test]
+            #[should_panic = "pushed"]
+            fn push_then_panic(&mut self) {
+                self.push(1);
+                panic!("pushed {} items", self.len());
+            }
+
+            #[test]
+            fn without_fixture() {}
+        }
+
+        struct Capped {
+            items: std::vec::Vec<u32>,
+            capacity: usize,
+        }
+
+        impl Capped {
+            fn with_capacity(capacity: usize) -> Self {
+                Self {
+                    items: std::vec::Vec::new(),
+                    capacity,
+                }
+            }
+        }
+
+        #[crate::test_impl(fixtures(
+            empty = Capped::with_capacity(4),
+            prefilled = Capped { items: std::vec![1, 2], capacity: 4 },
+        ))]
+        impl Stack for Capped {
+            fn push(&mut self, item: u32) {
+                assert!(self.items.len() < self.capacity, "over capacity");
+                self.items.push(item);
+            }
+            fn pop(&mut self) -> Option<u32> {
+                self.items.pop()
+            }
+            fn len(&self) -> usize {
+                self.items.len()
+            }
+        }
+
+        #[crate::test_impl(fixture = std::vec![7])]
+        impl Stack for std::vec::Vec<u32> {
+            fn push(&mut self, item: u32) {
+                self.push(item);
+            }
+            fn pop(&mut self) -> Option<u32> {
+                self.pop()
+            }
+            fn len(&self) -> usize {
+                self.len()
+            }
+        }
+
+        #[test]
+        fn run_against_each_fixture() {
+            let suite = <Capped as Stack>::tested_trait_suite();
+            assert_eq!(
+                names(suite),
+                [
+                    "push_then_pop::empty",
+                    "push_then_pop::prefilled",
+                    "push_grows_case_1::empty",
+                    "push_grows_case_1::prefilled",
+                    "push_grows_case_2_max::empty",
+                    "push_grows_case_2_max::prefilled",
+                    "clone_is_equal::empty",
+                    "clone_is_equal::prefilled",
+                    "push_then_panic::empty",
+                    "push_then_panic::prefilled",
+                    "without_fixture",
+                ]
+            );
+            for case in suite {
+                match (case.run)() {
+                    Outcome::Passed => assert!(!case.name.starts_with("clone_is_equal")),
+                    Outcome::Skipped { .. } => assert!(case.name.starts_with("clone_is_equal")),
+                    Outcome::Failed { message } => panic!("{} failed: {message}", case.name),
+                }
+            }
+        }
+
+        #[test]
+        fn single_fixture_keeps_names() {
+            let suite = <std::vec::Vec<u32> as Stack>::tested_trait_suite();
+            assert_eq!(suite[0].name, "push_then_pop");
+            assert_passes(suite);
+        }
+
+        #[test]
+        fn in_integration_test() {
+            struct Unbounded(std::vec::Vec<u32>);
+
+            #[crate::test_impl(fixture = Unbounded(std::vec::Vec::new()))]
+            #[in_integration_test]
+            impl Stack for Unbounded {
+                fn push(&mut self, item: u32) {
+                    self.0.push(item);
+                }
+                fn pop(&mut self) -> Option<u32> {
+                    self.0.pop()
+                }
+                fn len(&self) -> usize {
+                    self.0.len()
+                }
+            }
+        }
+    }
+
+    mod generic_tests {
+        use core::fmt::Debug;
+        use std::{boxed::Box, rc::Rc, string::String, vec::Vec};
+
+        use super::{assert_passes, names};
+        use crate::Outcome;
+
+        #[crate::tested_trait(types(U = [u8, String]))]
+        trait Wrapper {
+            type Of<T>;
+
+            fn wrap<T>(item: T) -> Self::Of<T>;
+            fn unwrap<T: Clone>(wrapped: &Self::Of<T>) -> T;
+
+            #[test]
+            fn round_trip<U: Default + Clone + PartialEq + Debug>() {
+                let item = U::default();
+                assert_eq!(Self::unwrap(&Self::wrap(item.clone())), item);
+            }
+
+            #[test]
+            fn arrays<const N: usize>() {
+                assert_eq!(Self::unwrap(&Self::wrap([7; N])), [7; N]);
+            }
+
+            #[test]
+            #[optional]
+            fn wrapped_is_clone<U: Default + Clone + PartialEq + Debug>()
+            where
+                Self::Of<U>: Clone,
+            {
+                let wrapped = Self::wrap(U::default());
+                assert_eq!(Self::unwrap(&wrapped.clone()), U::default());
+            }
+        }
+
+        struct Boxed;
+
+        #[crate::test_impl(types(N = [0, 3]))]
+        impl Wrapper for Boxed {
+            type Of<T> = Box<T>;
+
+            fn wrap<T>(item: T) -> Box<T> {
+                Box::new(item)
+            }
+            fn unwrap<T: Clone>(wrapped: &Box<T>) -> T {
+                T::clone(wrapped)
+            }
+        }
+
+        struct Counted;
+
+        struct Unique<T>(Rc<T>);
+
+        #[crate::test_impl(types(U = [Vec<u64>, (bool, char)], N = [1]))]
+        impl Wrapper for Counted {
+            type Of<T> = Unique<T>;
+
+            fn wrap<T>(item: T) -> Unique<T> {
+                Unique(Rc::new(item))
+            }
+            fn unwrap<T: Clone>(wrapped: &Unique<T>) -> T {
+                T::clone(&wrapped.0)
+            }
+        }
+
+        #[test]
+        fn instantiated_with_trait_types() {
+            let suite = <Boxed as Wrapper>::tested_trait_suite();
+            assert_eq!(
+                names(suite),
+                [
+                    "round_trip_u8",
+                    "round_trip_String",
+                    "arrays_0",
+                    "arrays_3",
+                    "wrapped_is_clone_u8",
+                    "wrapped_is_clone_String",
+                ]
+            );
+            assert_passes(suite);
+        }
+
+        #[test]
+        fn instantiated_with_impl_types() {
+            let suite = <Counted as Wrapper>::tested_trait_suite();
+            assert_eq!(
+                names(suite),
+                [
+                    "round_trip_Vec_u64",
+                    "round_trip_bool_char",
+                    "arrays_1",
+                    "wrapped_is_clone_Vec_u64",
+                    "wrapped_is_clone_bool_char",
+                ]
+            );
+            for case in suite {
+                match (case.run)() {
+                    Outcome::Passed => assert!(!case.name.starts_with("wrapped_is_clone")),
+                    // `Unique` isn't `Clone`
+                    Outcome::Skipped { .. } => assert!(case.name.starts_with("wrapped_is_clone")),
+                    Outcome::Failed { message } => panic!("{} failed: {message}", case.name),
+                }
+            }
+        }
+    }
+
+    mod supertraits {
+        use core::fmt::Debug;
+        use std::{vec, vec::Vec};
+
+        use super::{assert_passes, names};
+
+        mod len {
+            #[crate::tested_trait]
+            pub trait Len {
+                fn len(&self) -> usize;
+
+                #[test]
+                #[optional]
+                fn empty_by_default()
+                where
+                    Self: Default,
+                {
+                    assert_eq!(Self::default().len(), 0);
+                }
+            }
+        }
+
+        use len::Len;
+
+        #[crate::tested_trait(include_supertraits)]
+        trait Stack<T>: Len {
+            fn push(&mut self, item: T);
+            fn pop(&mut self) -> Option<T>;
+
+            #[test]
+            fn push_then_pop(&mut self)
+            where
+                T: Default + PartialEq + Debug,
+            {
+                let len = self.len();
+                self.push(T::default());
+                assert_eq!(self.len(), len + 1);
+                assert_eq!(self.pop(), Some(T::default()));
+            }
+        }
+
+        #[crate::tested_trait(include_supertraits(Stack))]
+        trait Bounded<T>: Stack<T> + Clone {
+            fn capacity(&self) -> usize;
+
+            #[test]
+            fn within_capacity(&self) {
+                assert!(self.len() <= self.capacity());
+            }
+        }
+
+        #[derive(Clone, Default)]
+        struct Capped(Vec<u8>);
+
+        impl Len for Capped {
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+        }
+
+        impl Stack<u8> for Capped {
+            fn push(&mut self, item: u8) {
+                self.0.push(item);
+            }
+            fn pop(&mut self) -> Option<u8> {
+                self.0.pop()
+            }
+        }
+
+        #[crate::test_impl(fixtures(empty = Capped::default(), full = Capped(vec![1, 2])))]
+        impl Bounded<u8> for Capped {
+            fn capacity(&self) -> usize {
+                2
+            }
+        }
+
+        #[test]
+        fn includes_supertraits_transitively() {
+            let suite = <Capped as Bounded<u8>>::tested_trait_suite();
+            assert_passes(suite);
+            assert_eq!(
+                names(suite),
+                ["within_capacity::empty", "within_capacity::full"]
+            );
+            let suite = <Capped as Stack<u8>>::tested_trait_suite();
+            assert_passes(suite);
+            assert_eq!(
+                names(suite),
+                ["push_then_pop::empty", "push_then_pop::full"]
+            );
+            let suite = <Capped as Len>::tested_trait_suite();
+            assert_passes(suite);
+            assert_eq!(names(suite), ["empty_by_default"]);
+        }
+
+        #[test]
+        fn in_integration_test() {
+            struct Pile(Vec<u16>);
+
+            impl Len for Pile {
+                fn len(&self) -> usize {
+                    self.0.len()
+                }
+            }
+
+            // Skips `Len`'s `empty_by_default`, as `Pile` isn't `Default`
+            #[crate::test_impl(fixture = Pile(vec![3]))]
+            #[in_integration_test]
+            impl Stack<u16> for Pile {
+                fn push(&mut self, item: u16) {
+                    self.0.push(item);
+                }
+                fn pop(&mut self) -> Option<u16> {
+                    self.0.pop()
+                }
+            }
+        }
+    }
+
+    mod out_of_line {
+        use std::collections::VecDeque;
+
+        use super::{assert_passes, names};
+
+        #[crate::tested_trait(tests = self::queue_tests)]
+        trait Queue {
+            fn enqueue(&mut self, item: u8);
+            fn dequeue(&mut self) -> Option<u8>;
+
+            #[test]
+            #[optional]
+            fn starts_empty()
+            where
+                Self: Default,
+            {
+                assert_eq!(Self::default().dequeue(), None);
+            }
+        }
+
+        #[crate::trait_tests(for Queue)]
+        mod queue_tests {
+            use std::{vec, vec::Vec};
+
+            fn drain(queue: &mut impl Queue) -> Vec<u8> {
+                core::iter::from_fn(|| queue.dequeue()).collect()
+            }
+
+            #[test]
+            #[optional]
+            fn first_in_first_out()
+            where
+                Self: Default,
+            {
+                let mut queue = Self::default();
+                queue.enqueue(1);
+                queue.enqueue(2);
+                assert_eq!(drain(&mut queue), vec![1, 2]);
+            }
+        }
+
+        #[crate::trait_tests(for Queue)]
+        mod capacity_tests {
+            #[test]
+            fn holds_a_hundred(&mut self) {
+                for item in 0..100 {
+                    self.enqueue(item);
+                }
+                assert_eq!(self.dequeue(), Some(0));
+            }
+        }
+
+        #[derive(Default)]
+        struct Deque(VecDeque<u8>);
+
+        #[crate::test_impl(fixture = Deque::default(), tests(capacity_tests))]
+        impl Queue for Deque {
+            fn enqueue(&mut self, item: u8) {
+                self.0.push_back(item);
+            }
+            fn dequeue(&mut self) -> Option<u8> {
+                self.0.pop_front()
+            }
+        }
+
+        #[test]
+        fn includes_out_of_line_tests() {
+            let suite = <Deque as Queue>::tested_trait_suite();
+            assert_passes(suite);
+            assert_eq!(names(suite), ["starts_empty"]);
+            let suite = <Deque as queue_tests::queue_tests>::tested_trait_suite();
+            assert_passes(suite);
+            assert_eq!(names(suite), ["first_in_first_out"]);
+            let suite = <Deque as capacity_tests::capacity_tests>::tested_trait_suite();
+            assert_passes(suite);
+            assert_eq!(names(suite), ["holds_a_hundred"]);
+        }
+
+        #[test]
+        fn in_integration_test() {
+            struct Ring(VecDeque<u8>);
+
+            // Skips `starts_empty` and `first_in_first_out`, as `Ring` isn't `Default`
+            #[crate::test_impl(fixture = Ring(VecDeque::new()), tests(capacity_tests))]
+            #[in_integration_test]
+            impl Queue for Ring {
+                fn enqueue(&mut self, item: u8) {
+                    self.0.push_back(item);
+                }
+                fn dequeue(&mut self) -> Option<u8> {
+                    self.0.pop_front()
+                }
+            }
+        }
+    }
+
+    mod remote {
+        use std::{io::Read, vec::Vec};
+
+        use super::{assert_passes, names};
+
+        #[crate::tested_trait(remote = std::io::Read)]
+        trait ReadTests {
+            fn read_all(&mut self) -> Vec<u8> {
+                let mut buf = Vec::new();
+                self.read_to_end(&mut buf).unwrap();
+                buf
+            }
+
+            #[test]
+            fn reads_until_exhausted(&mut self) {
+                assert!(!self.read_all().is_empty());
+                assert!(self.read_all().is_empty());
+            }
+        }
+
+        #[crate::tested_trait(remote = core::convert::From<T>)]
+        trait FromTests<T> {
+            #[test]
+            fn from_default()
+            where
+                T: Default,
+            {
+                let _ = Self::from(T::default());
+            }
+        }
+
+        struct Bytes(&'static [u8]);
+
+        #[crate::test_impl(with = ReadTests, fixture = Bytes(b"abc"))]
+        impl Read for Bytes {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                self.0.read(buf)
+            }
+        }
+
+        struct Wrapper;
+
+        #[crate::test_impl(with = FromTests)]
+        impl From<u8> for Wrapper {
+            fn from(_: u8) -> Self {
+                Wrapper
+            }
+        }
+
+        #[test]
+        fn runs_remote_definitions() {
+            let suite = <Bytes as ReadTests>::tested_trait_suite();
+            assert_passes(suite);
+            assert_eq!(names(suite), ["reads_until_exhausted"]);
+            let suite = <Wrapper as FromTests<u8>>::tested_trait_suite();
+            assert_passes(suite);
+            assert_eq!(names(suite), ["from_default"]);
+        }
+
+        #[test]
+        fn in_integration_test() {
+            struct Flag;
+
+            #[crate::test_impl(with = FromTests)]
+            #[in_integration_test]
+            impl From<bool> for Flag {
+                fn from(_: bool) -> Self {
+                    Flag
+                }
+            }
+        }
+    }
+
+    mod standalone {
+        use std::{string::String, vec::Vec};
+
+        use super::assert_passes;
+
+        #[crate::tested_trait]
+        trait Sink {
+            fn put(&mut self, byte: u8);
+            fn size(&self) -> usize;
+
+            #[test]
+            fn put_grows_size(&mut self) {
+                let size = self.size();
+                self.put(b'a');
+                assert_eq!(self.size(), size + 1);
+            }
+        }
+
+        macro_rules! impl_sink {
+            ($($ty:ty),*) => {$(
+                impl Sink for $ty {
+                    fn put(&mut self, byte: u8) {
+                        self.push(byte.into());
+                    }
+                    fn size(&self) -> usize {
+                        self.len()
+                    }
+                }
+            )*};
+        }
+
+        impl_sink!(Vec<u8>, String);
+
+        crate::test_impls!(Vec<u8>: Sink, String: Sink, fixture = <_>::default());
+
+        #[derive(Default)]
+        struct Counter(usize);
+
+        impl Sink for Counter {
+            fn put(&mut self, _: u8) {
+                self.0 += 1;
+            }
+            fn size(&self) -> usize {
+                self.0
+            }
+        }
+
+        crate::test_impls!(Counter: Sink, name = "counter", fixture = Counter(3));
+
+        // Listed impls get the same module names as with `#[test_impl]`
+        #[allow(unused_imports)]
+        use {counter as _, Sink_for_String as _, Sink_for_Vec_u8 as _};
+
+        #[test]
+        fn runs_listed_impls() {
+            for suite in [
+                <Vec<u8> as Sink>::tested_trait_suite(),
+                <String as Sink>::tested_trait_suite(),
+                <Counter as Sink>::tested_trait_suite(),
+            ] {
+                assert_passes(suite);
+            }
+        }
+
+        #[test]
+        fn in_integration_test() {
+            struct Bytes(Vec<u8>);
+
+            impl Sink for Bytes {
+                fn put(&mut self, byte: u8) {
+                    self.0.put(byte);
+                }
+                fn size(&self) -> usize {
+                    self.0.size()
+                }
+            }
+
+            crate::test_impls!(
+                #[in_integration_test]
+                Bytes: Sink,
+                fixture = Bytes(Vec::from([1, 2])),
+            );
+        }
+    }
+
+    mod test_cases {
+        use crate::{Outcome, TestKind};
+
+        #[crate::tested_trait]
+        trait Foo {
+            #[test]
+            fn passes() {}
+
+            #[test]
+            #[should_panic]
+            #[serial]
+            fn panics() {
+                panic!()
+            }
+
+            #[test]
+            #[optional]
+            fn unsatisfied()
+            where
+                Self: Clone,
+            {
+            }
+
+            // Ignored so that it doesn't fail when run by the standard test harness
+            #[test]
+            #[ignore = "fails"]
+            fn fails() -> Result<(), &'static str> {
+                Err("failed as expected")
+            }
+        }
+
+        struct Bar;
+
+        #[crate::test_impl]
+        impl Foo for Bar {}
+
+        #[test]
+        fn listed_and_run() {
+            let suite = <Bar as Foo>::tested_trait_suite();
+            let cases = (suite.iter())
+                .map(|case| {
+                    (
+                        case.name,
+                        case.kind,
+                        case.ignored,
+                        case.ignore_reason,
+                        case.serial,
+                    )
+                })
+                .collect::<std::vec::Vec<_>>();
+            assert_eq!(
+                cases,
+                [
+                    ("passes", TestKind::Standard, false, None, false),
+                    ("panics", TestKind::ShouldPanic, false, None, true),
+                    ("unsatisfied", TestKind::Standard, false, None, false),
+                    ("fails", TestKind::ReturnsResult, true, Some("fails"), false),
+                ]
+            );
+            let outcomes = suite
+                .iter()
+                .map(|case| (case.run)())
+                .collect::<std::vec::Vec<_>>();
+            assert_eq!(
+                outcomes,
+                [
+                    Outcome::Passed,
+                    Outcome::Passed,
+                    Outcome::Skipped {
+                        reason: "the implementation does not satisfy `Self: Clone`".into()
+                    },
+                    Outcome::Failed {
+                        message:
+                            "called `Result::unwrap()` on an `Err` value: \"failed as expected\""
+                                .into()
+                    },
+                ]
+            );
+        }
+
+        #[crate::tested_trait]
+        trait Baz<T = u8, const N: usize = 2> {
+            #[test]
+            fn first() {}
+
+            #[test]
+            fn second() {}
+        }
+
+        #[crate::test_impl]
+        impl Baz for Bar {}
+
+        #[crate::test_impl(Bar: Baz<u16, 3>)]
+        impl<const N: usize> Baz<u16, N> for Bar {}
+
+        #[test]
+        fn per_generic_args() {
+            assert_eq!(<Bar as Baz>::tested_trait_suite().len(), 2);
+            assert_eq!(<Bar as Baz<u8, 2>>::tested_trait_suite().len(), 2);
+            assert_eq!(<Bar as Baz<u16, 3>>::tested_trait_suite().len(), 2);
+        }
+    }
+
+    #[test]
+    fn skipped_names_unsatisfied_bounds() {
+        let skipped = crate::runner::select_optional(
+            None,
+            &[
+                ("Self: Default", false),
+                ("T: Clone", true),
+                ("T: Debug", false),
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(
+            std::string::ToString::to_string(&skipped),
+            "the implementation does not satisfy `Self: Default`, `T: Debug`"
+        );
+    }
+
+    #[cfg(feature = "proptest")]
+    mod proptests {
+        use crate::proptest::prelude::*;
+
+        #[super::tested_trait(proptest(cases = 64))]
+        trait Codec {
+            fn encode(value: u32) -> Vec<u8>;
+            fn decode(bytes: &[u8]) -> u32;
+
+            #[test]
+            #[proptest]
+            fn roundtrip(value: u32) {
+                prop_assert_eq!(Self::decode(&Self::encode(value)), value);
+            }
+
+            #[test]
+            #[proptest(cases = 16)]
+            fn decode_ignores_trailing_bytes(
+                #[strategy(0..1000u32)] value: u32,
+                trailing: Vec<u8>,
+            ) {
+                let mut bytes = Self::encode(value);
+                bytes.extend(trailing);
+                assert_eq!(Self::decode(&bytes), value);
+            }
+        }
+
+        struct LittleEndian;
+
+        #[super::test_impl]
+        impl Codec for LittleEndian {
+            fn encode(value: u32) -> Vec<u8> {
+                value.to_le_bytes().to_vec()
+            }
+
+            fn decode(bytes: &[u8]) -> u32 {
+                u32::from_le_bytes(bytes[..4].try_into().unwrap())
+            }
+        }
+
+        #[test]
+        fn failures_are_shrunk() {
+            let err = std::panic::catch_unwind(|| {
+                crate::__private::run_proptest::<(), _>(
+                    "Foo",
+                    "below_100",
+                    None,
+                    None,
+                    (any::<u32>(),),
+                    |input: &(u32,), f: &mut core::fmt::Formatter<'_>| {
+                        write!(f, "x = {:?}", input.0)
+                    },
+                    |(x,): (u32,)| {
+                        assert!(x < 100);
+                        Ok(())
+                    },
+                );
+            })
+            .unwrap_err();
+            let message = err.downcast_ref::<String>().unwrap();
+            assert!(
+                message.starts_with("`below_100` failed for `Foo for ()`: "),
+                "{message}"
+            );
+            assert!(
+                message.ends_with("minimal failing input: x = 100"),
+                "{message}"
+            );
+        }
+    }
+
+    #[cfg(feature = "quickcheck")]
+    mod quickchecks {
+        use crate::quickcheck::TestResult;
+
+        #[super::tested_trait(quickcheck(tests = 50))]
+        trait Checked {
+            fn checked_div(a: u8, b: u8) -> Option<u8>;
+
+            #[test]
+            #[quickcheck]
+            fn quotient_is_at_most_dividend(a: u8, b: u8) -> TestResult {
+                if b == 0 {
+                    return TestResult::discard();
+                }
+                TestResult::from_bool(Self::checked_div(a, b).unwrap() <= a)
+            }
+
+            #[test]
+            #[quickcheck(tests = 10)]
+            fn division_by_zero_fails(a: u8) -> bool {
+                Self::checked_div(a, 0).is_none()
+            }
+        }
+
+        #[super::test_impl]
+        impl Checked for u8 {
+            fn checked_div(a: u8, b: u8) -> Option<u8> {
+                a.checked_div(b)
+            }
+        }
+
+        #[test]
+        fn failures_name_the_implementation() {
+            let err = std::panic::catch_unwind(|| {
+                crate::__private::run_quickcheck::<(), _>(
+                    "Foo",
+                    "below_100",
+                    None,
+                    (|x: u32| x < 100) as fn(u32) -> bool,
+                );
+            })
+            .unwrap_err();
+            let message = err.downcast_ref::<String>().unwrap();
+            assert!(
+                message.starts_with("`below_100` failed for `Foo for ()`: "),
+                "{message}"
+            );
+            assert!(message.contains("Arguments: (100)"), "{message}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "harness")]
+    fn harness_args() {
+        use crate::harness::Args;
+
+        let args = Args::parse(
+            [
+                "--format=terse",
+                "alloc",
+                "--skip",
+                "slow",
+                "--test-threads",
+                "2",
+                "--exact",
+            ]
+            .map(std::string::String::from),
+        )
+        .unwrap();
+        assert!(args.terse && args.exact);
+        assert!(args.matches("alloc"));
+        assert!(!args.matches("alloc_respects_alignment"));
+        assert!(!args.matches("slow"));
+        assert_eq!(args.test_threads.map(core::num::NonZeroUsize::get), Some(2));
+        assert!(Args::parse([std::string::String::from("--bogus")]).is_err());
+    }
+
+    #[cfg(feature = "harness")]
+    mod harness_run {
+        use std::{string::String, vec::Vec};
+
+        use crate::harness::{registered_tests, run, Args};
+
+        #[crate::tested_trait]
+        trait Foo {
+            #[test]
+            fn passes() {}
+
+            #[test]
+            #[ignore = "fails"]
+            fn fails() {
+                panic!("failed as expected")
+            }
+        }
+
+        #[crate::test_impl]
+        impl Foo for () {}
+
+        /// Runs the registered tests on one thread (to fix their order) with `args`, returning
+        /// whether they passed, i.e. whether `harness::main()` would exit with status `0`, and
+        /// the output without the filtered out count and timing of the summary, which vary.
+        fn run_with(args: &[&str]) -> (bool, String) {
+            let args = ["--test-threads=1"].iter().chain(args);
+            let args = Args::parse(args.map(|&arg| String::from(arg))).unwrap();
+            let mut out = Vec::new();
+            let passed = run(&args, registered_tests(), &mut out).unwrap();
+            let out = (String::from_utf8(out).unwrap().lines())
+                .map(|line| match line.find(" ignored;") {
+                    Some(end) if line.starts_with("test result:") => &line[..end + 8],
+                    _ => line,
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            (passed, out)
+        }
+
+        #[test]
+        fn filters() {
+            assert_eq!(
+                run_with(&["harness_run::"]),
+                (
+                    true,
+                    String::from(
+                        "
+running 2 tests
+test tests::harness_run::Foo_for_unit::fails ... ignored, fails
+test tests::harness_run::Foo_for_unit::passes ... ok
+
+test result: ok. 1 passed; 0 failed; 1 ignored
+"
+                    )
+                )
+            );
+            assert_eq!(
+                run_with(&["harness_run::", "--skip", "passes"]),
+                (
+                    true,
+                    String::from(
+                        "
+running 1 test
+test tests::harness_run::Foo_for_unit::fails ... ignored, fails
+
+test result: ok. 0 passed; 0 failed; 1 ignored
+"
+                    )
+                )
+            );
+        }
+
+        #[test]
+        fn exact() {
+            let (passed, out) = run_with(&["--exact", "tests::harness_run::Foo_for_unit::passes"]);
+            assert!(passed);
+            assert!(out.starts_with("\nrunning 1 test\n"), "{out}");
+            let (passed, out) = run_with(&["--exact", "harness_run::"]);
+            assert!(passed);
+            assert!(out.starts_with("\nrunning 0 tests\n"), "{out}");
+        }
+
+        #[test]
+        fn ignored() {
+            let (passed, out) = run_with(&["harness_run::", "--ignored"]);
+            assert!(!passed);
+            // The panic is captured along with its location
+            let (start, end) = out
+                .split_once(&std::format!(" panicked at {}:", file!()))
+                .unwrap();
+            assert_eq!(
+                start,
+                "
+running 1 test
+test tests::harness_run::Foo_for_unit::fails ... FAILED
+
+failures:
+
+---- tests::harness_run::Foo_for_unit::fails stdout ----
+
+thread 'tests::harness_run::Foo_for_unit::fails'"
+            );
+            assert!(
+                end.ends_with(
+                    ":
+failed as expected
+
+
+failures:
+    tests::harness_run::Foo_for_unit::fails
+
+test result: FAILED. 0 passed; 1 failed; 0 ignored
+"
+                ),
+                "{end}"
+            );
+            let (passed, out) = run_with(&["harness_run::", "--ignored", "--nocapture"]);
+            assert!(!passed);
+            assert!(
+                out.contains("---- tests::harness_run::Foo_for_unit::fails stdout ----\nfailed as expected\n\n"),
+                "{out}"
+            );
+            let (passed, out) = run_with(&["harness_run::", "--include-ignored"]);
+            assert!(!passed);
+            assert!(out.contains("\nrunning 2 tests\n"), "{out}");
+        }
+
+        #[test]
+        fn list() {
+            assert_eq!(
+                run_with(&["harness_run::", "--list"]),
+                (
+                    true,
+                    String::from(
+                        "tests::harness_run::Foo_for_unit::fails: test
+tests::harness_run::Foo_for_unit::passes: test
+
+2 tests, 0 benchmarks"
+                    )
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn report_formats() {
+        use crate::report::{json, junit, Status, SuiteReport, TestReport};
+        use core::time::Duration;
+
+        let suite = SuiteReport {
+            trait_name: "Foo".into(),
+            impl_name: "Foo<u8> for Bar".into(),
+            tests: vec![
+                TestReport {
+                    name: "passes",
+                    status: Status::Passed,
+                    duration: Duration::from_millis(1500),
+                },
+                TestReport {
+                    name: "fails",
+                    status: Status::Failed {
+                        message: "expected \"a\"\nfound <b>".into(),
+                    },
+                    duration: Duration::ZERO,
+                },
+                TestReport {
+                    name: "ignored",
+                    status: Status::Ignored { reason: None },
+                    duration: Duration::ZERO,
+                },
+                TestReport {
+                    name: "skipped",
+                    status: Status::Skipped {
+                        reason: "the implementation does not satisfy `Self: Default`".into(),
+                    },
+                    duration: Duration::ZERO,
+                },
+            ],
+        };
+        assert_eq!(
+            json(std::slice::from_ref(&suite)),
+            concat!(
+                r#"{"suites":[{"trait":"Foo","impl":"Foo<u8> for Bar","tests":["#,
+                r#"{"name":"passes","status":"passed","duration_secs":1.5},"#,
+                r#"{"name":"fails","status":"failed","duration_secs":0,"message":"expected \"a\"\nfound <b>"},"#,
+                r#"{"name":"ignored","status":"ignored","duration_secs":0},"#,
+                r#"{"name":"skipped","status":"skipped","duration_secs":0,"reason":"the implementation does not satisfy `Self: Default`"}"#,
+                "]}]}\n",
+            )
+        );
+        assert_eq!(
+            junit(&[suite]),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+  <testsuite name="impl Foo&lt;u8&gt; for Bar" tests="4" failures="1" skipped="2" time="1.5">
+    <testcase classname="impl Foo&lt;u8&gt; for Bar" name="Foo::passes" time="1.5"/>
+    <testcase classname="impl Foo&lt;u8&gt; for Bar" name="Foo::fails" time="0">
+      <failure message="expected &quot;a&quot;&#10;found &lt;b&gt;">expected &quot;a&quot;&#10;found &lt;b&gt;</failure>
+    </testcase>
+    <testcase classname="impl Foo&lt;u8&gt; for Bar" name="Foo::ignored" time="0">
+      <skipped/>
+    </testcase>
+    <testcase classname="impl Foo&lt;u8&gt; for Bar" name="Foo::skipped" time="0">
+      <skipped message="the implementation does not satisfy `Self: Default`"/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+
+    mod test_fn_reports {
+        use core::num::NonZeroU8;
+
+        #[super::tested_trait]
+        trait Foo {
+            #[test]
+            fn passes() {}
+
+            #[test]
+            #[should_panic = "as expected"]
+            fn panics() {
+                panic!("panicked as expected")
+            }
+
+            #[test]
+            #[optional]
+            fn skipped()
+            where
+                Self: Default,
+            {
+            }
+        }
+
+        #[super::test_impl]
+        impl Foo for NonZeroU8 {}
+
+        #[test]
+        fn recorded() {
+            let path = std::env::temp_dir().join(std::format!(
+                "tested-trait-test-fn-reports-{}.json",
+                std::process::id()
+            ));
+            // Reruns this binary's tests of `Foo for NonZeroU8` with a report requested
+            let output = std::process::Command::new(std::env::current_exe().unwrap())
+                .arg("tests::test_fn_reports::Foo_for_NonZeroU8::")
+                .env("TESTED_TRAIT_REPORT", &path)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{output:?}");
+            let report = std::fs::read_to_string(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            assert!(report.starts_with(r#"{"suites":[{"trait":"Foo","impl":"Foo for NonZeroU8","#));
+            for test in [
+                r#"{"name":"passes","status":"passed","#,
+                r#"{"name":"panics","status":"passed","#,
+                r#"{"name":"skipped","status":"skipped","#,
+            ] {
+                assert!(report.contains(test), "{report}");
+            }
+            assert!(
+                report
+                    .contains(r#""reason":"the implementation does not satisfy `Self: Default`""#),
+                "{report}"
+            );
+        }
+
+        #[test]
+        fn skip_printed() {
+            // Reruns only the skipped test, whose output libtest would otherwise capture
+            let output = std::process::Command::new(std::env::current_exe().unwrap())
+                .args([
+                    "--exact",
+                    "tests::test_fn_reports::Foo_for_NonZeroU8::skipped",
+                ])
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{output:?}");
+            let stdout = std::string::String::from_utf8(output.stdout).unwrap();
+            assert!(
+                stdout.contains(
+                    "skipping skipped for impl Foo for NonZeroU8: \
+                     the implementation does not satisfy `Self: Default`\n"
+                ),
+                "{stdout}"
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic = "test did not panic as expected"]
+    fn should_panic_doesnt_panic() {
+        #[tested_trait]
+        trait Foo {
+            #[test]
+            #[should_panic]
+            fn doesnt_panic() {}
+        }
+
+        #[test_impl]
+        #[in_integration_test]
+        impl Foo for () {}
+    }
+
+    #[test]
+    fn integration_test_skips_ignored_tests() {
+        #[tested_trait]
+        trait Foo {
+            #[test]
+            #[ignore]
+            fn ignored() {
+                panic!("ignored tests shouldn't run by default")
+            }
+
+            #[test]
+            #[ignore = "reason"]
+            fn ignored_with_reason() {
+                panic!("ignored tests shouldn't run by default")
+            }
+        }
+
+        #[test_impl]
+        #[in_integration_test]
+        impl Foo for () {}
+    }
+
+    #[test]
+    fn integration_test_runs_only_ignored_tests_with_ignored_flag() {
+        use crate::runner::{run_suite_with, Ignore, RunIgnored, Test};
+        use core::sync::atomic::{AtomicBool, Ordering};
+
+        static RAN: AtomicBool = AtomicBool::new(false);
+
+        let tests = [
+            Test {
+                name: "not_ignored",
+                ignore: Ignore::No,
+                timeout: None,
+                serial: false,
+                run: Ok(|| panic!("tests that aren't ignored shouldn't run with `--ignored`")),
+            },
+            Test {
+                name: "ignored",
+                ignore: Ignore::Yes,
+                timeout: None,
+                serial: false,
+                run: Ok(|| RAN.store(true, Ordering::SeqCst)),
+            },
+        ];
+        run_suite_with("Foo", "Foo for ()", false, &tests, RunIgnored::Only);
+        assert!(RAN.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    #[should_panic = "2 of 4 tests failed for impl Foo for (): first, third"]
+    fn parallel_failures_reported_in_order() {
+        use std::{
+            sync::atomic::{AtomicUsize, Ordering},
+            thread,
+            time::Duration,
+        };
+
+        static RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+        fn run_for_a_while() {
+            RUNNING.fetch_add(1, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(20));
+            RUNNING.fetch_sub(1, Ordering::SeqCst);
+        }
+
+        #[tested_trait(parallel)]
+        trait Foo {
+            #[test]
+            fn first() {
+                run_for_a_while();
+                panic!("first failed")
+            }
+
+            #[test]
+            fn second() {
+                run_for_a_while();
+            }
+
+            #[test]
+            fn third() {
+                run_for_a_while();
+                panic!("third failed")
+            }
+
+            #[test]
+            #[serial]
+            fn serial() {
+                thread::sleep(Duration::from_millis(20));
+                assert_eq!(RUNNING.load(Ordering::SeqCst), 0);
+            }
+        }
+
+        #[test_impl]
+        #[in_integration_test]
+        impl Foo for () {}
+    }
+
+    #[test]
+    #[should_panic = "associated test `hangs` for impl `Foo for ()` exceeded 50ms"]
+    fn timeout_exceeded() {
+        #[tested_trait]
+        trait Foo {
+            #[test]
+            #[timeout(ms = 50)]
+            fn hangs() {
+                loop {
+                    std::thread::park();
+                }
+            }
+        }
+
+        #[test_impl]
+        #[in_integration_test]
+        impl Foo for () {}
+    }
+
+    #[test]
+    #[should_panic = "2 of 3 tests failed for impl Foo for (): first, third"]
+    fn integration_test_runs_all_tests() {
+        #[tested_trait]
+        trait Foo {
+            #[test]
+            fn first() {
+                panic!("first failed")
+            }
+
+            #[test]
+            fn second() {}
+
+            #[test]
+            fn third() {
+                panic!("third failed")
+            }
         }
 
         #[test_impl]
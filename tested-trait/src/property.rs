@@ -0,0 +1,104 @@
+//! Runs property-based associated tests, i.e. `#[proptest]`s and `#[quickcheck]`s.
+
+use core::{
+    any,
+    fmt::{self, Display},
+};
+
+#[cfg(feature = "proptest")]
+use proptest::{
+    strategy::Strategy,
+    test_runner::{Config, TestCaseError, TestError, TestRunner},
+};
+#[cfg(feature = "quickcheck")]
+use quickcheck::{QuickCheck, Testable};
+#[cfg(feature = "quickcheck")]
+use std::panic::{self, AssertUnwindSafe};
+
+/// Runs a `#[proptest]` associated test of `Impl`'s implementation of `trait_name` for each of its
+/// cases, panicking with the failure and its minimal (shrunk) input if any case fails.
+///
+/// `cases` overrides the number of cases set by `config`, which defaults to proptest's default
+/// configuration.
+///
+/// # Panics
+///
+/// If a case fails, or proptest aborts the test.
+#[cfg(feature = "proptest")]
+pub fn run_proptest<Impl: ?Sized, S: Strategy>(
+    trait_name: &str,
+    test: &str,
+    config: Option<Config>,
+    cases: Option<u32>,
+    strategy: S,
+    describe: impl Fn(&S::Value, &mut fmt::Formatter<'_>) -> fmt::Result,
+    run: impl Fn(S::Value) -> Result<(), TestCaseError>,
+) {
+    // Failures aren't persisted by default, as proptest would look for the trait's source file
+    // relative to the crate testing the implementation
+    let mut config = config.unwrap_or_else(|| Config {
+        failure_persistence: None,
+        ..Config::default()
+    });
+    if let Some(cases) = cases {
+        config.cases = cases;
+    }
+    let impl_name = impl_name::<Impl>(trait_name);
+    match TestRunner::new(config).run(&strategy, run) {
+        Ok(()) => {}
+        Err(TestError::Fail(reason, input)) => {
+            let input = Describe(|f: &mut fmt::Formatter<'_>| describe(&input, f));
+            panic!("`{test}` failed for `{impl_name}`: {reason}\nminimal failing input: {input}")
+        }
+        Err(TestError::Abort(reason)) => {
+            panic!("`{test}` was aborted for `{impl_name}`: {reason}")
+        }
+    }
+}
+
+/// Runs a `#[quickcheck]` associated test of `Impl`'s implementation of `trait_name`, panicking
+/// with quickcheck's description of the (shrunk) failing arguments if it fails.
+///
+/// `tests` overrides the number of passing tests quickcheck requires, which is otherwise
+/// configured by its environment variables.
+///
+/// # Panics
+///
+/// If the property fails for any of the generated arguments.
+#[cfg(feature = "quickcheck")]
+pub fn run_quickcheck<Impl: ?Sized, F: Testable>(
+    trait_name: &str,
+    test: &str,
+    tests: Option<u64>,
+    property: F,
+) {
+    let mut quickcheck = QuickCheck::new();
+    if let Some(tests) = tests {
+        quickcheck = quickcheck.tests(tests);
+    }
+    // quickcheck panics to report failures, so its message is extended with the implementation.
+    // Neither `quickcheck` nor `property` is used after a panic.
+    let result = panic::catch_unwind(AssertUnwindSafe(move || quickcheck.quickcheck(property)));
+    if let Err(err) = result {
+        let message = crate::runner::panic_message(&*err);
+        panic!(
+            "`{test}` failed for `{}`: {message}",
+            impl_name::<Impl>(trait_name)
+        );
+    }
+}
+
+fn impl_name<Impl: ?Sized>(trait_name: &str) -> impl Display + '_ {
+    Describe(move |f: &mut fmt::Formatter<'_>| {
+        write!(f, "{trait_name} for {}", any::type_name::<Impl>())
+    })
+}
+
+/// Displays the output of a formatting function.
+struct Describe<F>(F);
+
+impl<F: Fn(&mut fmt::Formatter<'_>) -> fmt::Result> Display for Describe<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.0)(f)
+    }
+}
@@ -0,0 +1,236 @@
+//! Machine-readable reports of associated test outcomes, written to the file named by the
+//! `TESTED_TRAIT_REPORT` environment variable: `JUnit` XML if it ends in `.xml`, otherwise JSON.
+
+use core::{fmt::Write as _, time::Duration};
+use std::{
+    env, eprintln, fs,
+    path::Path,
+    string::String,
+    sync::{Mutex, PoisonError},
+    vec,
+    vec::Vec,
+};
+
+const REPORT_VAR: &str = "TESTED_TRAIT_REPORT";
+
+/// Suites run so far by this process, since each report covers all of them
+static SUITES: Mutex<Vec<SuiteReport>> = Mutex::new(Vec::new());
+
+/// The outcomes of a suite's tests for one implementation.
+pub struct SuiteReport {
+    pub trait_name: String,
+    pub impl_name: String,
+    pub tests: Vec<TestReport>,
+}
+
+/// The outcome of a single associated test.
+pub struct TestReport {
+    pub name: &'static str,
+    pub status: Status,
+    pub duration: Duration,
+}
+
+pub enum Status {
+    Passed,
+    Failed { message: String },
+    Ignored { reason: Option<&'static str> },
+    Skipped { reason: String },
+}
+
+impl Status {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Passed => "passed",
+            Self::Failed { .. } => "failed",
+            Self::Ignored { .. } => "ignored",
+            Self::Skipped { .. } => "skipped",
+        }
+    }
+}
+
+/// Records a suite's outcomes, rewriting the report (if requested) with every suite run so far.
+pub fn record(suite: SuiteReport) {
+    update(|suites| suites.push(suite));
+}
+
+/// Records the outcome of one of a suite's tests, which joins those of the suite's tests already
+/// recorded, as for the standard test harness's tests that each run one associated test.
+pub fn record_test(trait_name: &str, impl_name: &str, test: TestReport) {
+    update(|suites| {
+        let suite = (suites.iter_mut())
+            .find(|suite| suite.trait_name == trait_name && suite.impl_name == impl_name);
+        match suite {
+            Some(suite) => suite.tests.push(test),
+            None => suites.push(SuiteReport {
+                trait_name: trait_name.into(),
+                impl_name: impl_name.into(),
+                tests: vec![test],
+            }),
+        }
+    });
+}
+
+/// Updates the suites run so far and rewrites the report with them, if a report was requested.
+///
+/// Failing to write the report is only a warning, since it shouldn't obscure the tests' outcomes.
+fn update(f: impl FnOnce(&mut Vec<SuiteReport>)) {
+    let Some(path) = env::var_os(REPORT_VAR) else {
+        return;
+    };
+    let path = Path::new(&path);
+    let mut suites = SUITES.lock().unwrap_or_else(PoisonError::into_inner);
+    f(&mut suites);
+    let report = match path.extension() {
+        Some(extension) if extension == "xml" => junit(&suites),
+        _ => json(&suites),
+    };
+    let written = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => fs::create_dir_all(parent),
+        _ => Ok(()),
+    }
+    .and_then(|()| fs::write(path, report));
+    if let Err(err) = written {
+        eprintln!(
+            "warning: failed to write {REPORT_VAR}={}: {err}",
+            path.display()
+        );
+    }
+}
+
+pub fn json(suites: &[SuiteReport]) -> String {
+    let mut out = String::from("{\"suites\":[");
+    for (i, suite) in suites.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("{\"trait\":");
+        json_string(&mut out, &suite.trait_name);
+        out.push_str(",\"impl\":");
+        json_string(&mut out, &suite.impl_name);
+        out.push_str(",\"tests\":[");
+        for (i, test) in suite.tests.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str("{\"name\":");
+            json_string(&mut out, test.name);
+            let _ = write!(
+                out,
+                ",\"status\":\"{}\",\"duration_secs\":{}",
+                test.status.as_str(),
+                test.duration.as_secs_f64()
+            );
+            let detail = match &test.status {
+                Status::Passed | Status::Ignored { reason: None } => None,
+                Status::Failed { message } => Some(("message", message.as_str())),
+                Status::Ignored {
+                    reason: Some(reason),
+                } => Some(("reason", *reason)),
+                Status::Skipped { reason } => Some(("reason", reason.as_str())),
+            };
+            if let Some((key, value)) = detail {
+                let _ = write!(out, ",\"{key}\":");
+                json_string(&mut out, value);
+            }
+            out.push('}');
+        }
+        out.push_str("]}");
+    }
+    out.push_str("]}\n");
+    out
+}
+
+fn json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+pub fn junit(suites: &[SuiteReport]) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+    for suite in suites {
+        let count = |status: fn(&Status) -> bool| {
+            (suite.tests.iter())
+                .filter(|test| status(&test.status))
+                .count()
+        };
+        let duration: Duration = suite.tests.iter().map(|test| test.duration).sum();
+        let _ = writeln!(
+            out,
+            "  <testsuite name=\"impl {}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">",
+            xml_escape(&suite.impl_name),
+            suite.tests.len(),
+            count(|status| matches!(status, Status::Failed { .. })),
+            count(|status| matches!(status, Status::Ignored { .. } | Status::Skipped { .. })),
+            duration.as_secs_f64(),
+        );
+        for test in &suite.tests {
+            let _ = write!(
+                out,
+                "    <testcase classname=\"impl {}\" name=\"{}::{}\" time=\"{}\"",
+                xml_escape(&suite.impl_name),
+                xml_escape(&suite.trait_name),
+                xml_escape(test.name),
+                test.duration.as_secs_f64(),
+            );
+            match &test.status {
+                Status::Passed => out.push_str("/>\n"),
+                Status::Failed { message } => {
+                    let message = xml_escape(message);
+                    let _ = writeln!(
+                        out,
+                        ">\n      <failure message=\"{message}\">{message}</failure>\n    </testcase>"
+                    );
+                }
+                Status::Ignored { reason: None } => {
+                    out.push_str(">\n      <skipped/>\n    </testcase>\n");
+                }
+                Status::Ignored {
+                    reason: Some(reason),
+                } => junit_skipped(&mut out, reason),
+                Status::Skipped { reason } => junit_skipped(&mut out, reason),
+            }
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+fn junit_skipped(out: &mut String, reason: &str) {
+    let _ = writeln!(
+        out,
+        ">\n      <skipped message=\"{}\"/>\n    </testcase>",
+        xml_escape(reason)
+    );
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            // Other control characters aren't allowed in XML 1.0
+            c if c.is_control() && c != '\t' && c != '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,105 @@
+//! A hook for observing associated tests that are skipped, or run inline without the `std`
+//! feature, where there's no standard output to print their progress to.
+
+use core::{
+    fmt::{self, Display},
+    ptr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Receives the progress of associated tests that aren't reported by a test harness.
+///
+/// Without the `std` feature, implementations tested with `#[test_impl] #[in_integration_test]`
+/// report each test to the [`set_reporter()`] reporter rather than printing it. The reporter is
+/// also told about `#[optional]` tests that are skipped, which are otherwise printed to standard
+/// output with the `std` feature (even while the test harness captures it) and not reported at
+/// all without it.
+///
+/// Failing tests panic, so they aren't reported.
+pub trait Reporter: Sync {
+    /// Called before running an associated test inline
+    fn started(&self, impl_name: &str, test: &str) {
+        let _ = (impl_name, test);
+    }
+
+    /// Called after an associated test run inline passes
+    fn passed(&self, impl_name: &str, test: &str) {
+        let _ = (impl_name, test);
+    }
+
+    /// Called instead of running an `#[ignore]`d associated test inline
+    fn ignored(&self, impl_name: &str, test: &str, reason: Option<&str>) {
+        let _ = (impl_name, test, reason);
+    }
+
+    /// Called instead of running an `#[optional]` associated test whose bounds the implementation
+    /// doesn't satisfy
+    fn skipped(&self, impl_name: &str, test: &str, reason: &dyn Display) {
+        let _ = (impl_name, test, reason);
+    }
+}
+
+/// Prints skipped tests with the `std` feature, and otherwise does nothing.
+struct DefaultReporter;
+
+impl Reporter for DefaultReporter {
+    #[cfg(feature = "std")]
+    fn skipped(&self, impl_name: &str, test: &str, reason: &dyn Display) {
+        use std::io::Write as _;
+
+        // Writes to standard output directly, since the test harness would capture `println!`
+        // and only report the test as passing
+        let line = std::format!("skipping {test} for impl {impl_name}: {reason}\n");
+        let _ = std::io::stdout().lock().write_all(line.as_bytes());
+    }
+}
+
+const UNSET: usize = 0;
+const SETTING: usize = 1;
+const SET: usize = 2;
+
+static STATE: AtomicUsize = AtomicUsize::new(UNSET);
+static mut REPORTER: &dyn Reporter = &DefaultReporter;
+
+/// Sets the [`Reporter`] for all associated tests run by the process.
+///
+/// The reporter can only be set once.
+///
+/// # Errors
+///
+/// If the reporter has already been set.
+pub fn set_reporter(reporter: &'static dyn Reporter) -> Result<(), SetReporterError> {
+    match STATE.compare_exchange(UNSET, SETTING, Ordering::Acquire, Ordering::Relaxed) {
+        Ok(_) => {
+            // SAFETY: `STATE` ensures that this is the only write, and that `REPORTER` isn't
+            // read until it's complete
+            unsafe { REPORTER = reporter };
+            STATE.store(SET, Ordering::Release);
+            Ok(())
+        }
+        Err(_) => Err(SetReporterError(())),
+    }
+}
+
+/// The error returned by [`set_reporter()`] if a reporter has already been set.
+#[derive(Debug)]
+pub struct SetReporterError(());
+
+impl Display for SetReporterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a tested-trait reporter has already been set")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SetReporterError {}
+
+/// The reporter set by [`set_reporter()`], if any, or the default reporter.
+pub(crate) fn reporter() -> &'static dyn Reporter {
+    if STATE.load(Ordering::Acquire) == SET {
+        // SAFETY: `REPORTER` is never written again once `STATE` is `SET`
+        unsafe { *ptr::addr_of!(REPORTER) }
+    } else {
+        &DefaultReporter
+    }
+}
@@ -0,0 +1,586 @@
+//! Runtime support for running a suite of associated tests without the standard test harness.
+//!
+//! Without the `std` feature, only the core-only subset is available: tests run inline can't
+//! catch panics or time out, and report their progress through [`crate::Reporter`].
+
+#[cfg(feature = "std")]
+use core::num::NonZeroUsize;
+use core::time::Duration;
+use core::{
+    fmt,
+    future::Future,
+    pin::pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll, Waker},
+};
+#[cfg(feature = "std")]
+use std::{
+    any::Any,
+    env, panic, println,
+    string::String,
+    string::ToString,
+    sync::{mpsc, Arc, PoisonError, RwLock},
+    task::Wake,
+    thread::{self, Thread},
+    time::Instant,
+    vec::Vec,
+};
+
+use crate::reporter::reporter;
+#[cfg(feature = "std")]
+use crate::{
+    report::{self, Status, SuiteReport, TestReport},
+    Outcome,
+};
+
+/// An associated test instantiated for a concrete implementation.
+pub struct Test<'a> {
+    /// The name of the associated test
+    pub name: &'static str,
+    /// Whether the test is ignored, and if so, why
+    pub ignore: Ignore,
+    /// How long the test may run before failing, which requires the `std` feature
+    pub timeout: Option<Duration>,
+    /// Whether the test must not run concurrently with other associated tests
+    pub serial: bool,
+    /// Runs the test, panicking if it fails, or explains why it was skipped
+    pub run: Result<fn(), Skipped<'a>>,
+}
+
+/// Whether an associated test is ignored, from its `#[ignore]` or `#[ignore = "reason"]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ignore {
+    /// The test runs by default
+    No,
+    /// The test only runs when ignored tests are requested
+    Yes,
+    /// The test only runs when ignored tests are requested, and is ignored for the given reason
+    WithReason(&'static str),
+}
+
+impl Ignore {
+    pub(crate) fn is_ignored(self) -> bool {
+        !matches!(self, Self::No)
+    }
+
+    pub(crate) fn reason(self) -> Option<&'static str> {
+        match self {
+            Self::WithReason(reason) => Some(reason),
+            Self::No | Self::Yes => None,
+        }
+    }
+}
+
+/// An optional test that was skipped because the implementation doesn't satisfy its bounds,
+/// which are given alongside whether the implementation satisfies each individually.
+#[derive(Debug, Clone, Copy)]
+pub struct Skipped<'a> {
+    bounds: &'a [(&'static str, bool)],
+}
+
+impl fmt::Display for Skipped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // If the bounds are only unsatisfiable in combination, all of them are named
+        let all_satisfied = self.bounds.iter().all(|(_, satisfied)| *satisfied);
+        let unsatisfied = (self.bounds.iter())
+            .filter(|(_, satisfied)| all_satisfied || !satisfied)
+            .map(|(bound, _)| bound);
+        f.write_str("the implementation does not satisfy ")?;
+        for (i, bound) in unsatisfied.enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "`{bound}`")?;
+        }
+        Ok(())
+    }
+}
+
+/// Selects an optional test's function if the implementation satisfies all of its bounds, which
+/// are given alongside whether the implementation satisfies each individually.
+///
+/// # Errors
+///
+/// If the implementation doesn't satisfy all of the bounds, in which case the test is skipped.
+pub fn select_optional<'a>(
+    test: Option<fn()>,
+    bounds: &'a [(&'static str, bool)],
+) -> Result<fn(), Skipped<'a>> {
+    test.ok_or(Skipped { bounds })
+}
+
+/// Runs an optional test, or reports that it was skipped.
+pub fn run_or_skip(impl_name: &str, name: &str, test: Result<fn(), Skipped>) {
+    match test {
+        Ok(test) => test(),
+        Err(skipped) => reporter().skipped(impl_name, name, &skipped),
+    }
+}
+
+/// Runs every test in `tests` on the current thread, reporting their progress to the
+/// [`crate::Reporter`]. A failing test panics, stopping the remaining tests from running.
+///
+/// This is the core-only counterpart to `run_suite`: ignored tests never run, and timeouts have
+/// no effect.
+pub fn run_inline(impl_name: &str, tests: &[Test]) {
+    let reporter = reporter();
+    for test in tests {
+        if test.ignore.is_ignored() {
+            reporter.ignored(impl_name, test.name, test.ignore.reason());
+            continue;
+        }
+        match test.run {
+            Ok(run) => {
+                reporter.started(impl_name, test.name);
+                run_locked(test.serial, run);
+                reporter.passed(impl_name, test.name);
+            }
+            Err(skipped) => reporter.skipped(impl_name, test.name, &skipped),
+        }
+    }
+}
+
+/// Drives an `async` test's future to completion on the current thread, parking the thread while
+/// the future is pending.
+#[cfg(feature = "std")]
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    struct Unparker(Thread);
+
+    impl Wake for Unparker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(Unparker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// Drives an `async` test's future to completion on the current thread, polling it until it's
+/// ready since there are no threads to park without the `std` feature.
+#[cfg(not(feature = "std"))]
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    use core::{
+        hint, ptr,
+        task::{RawWaker, RawWakerVTable},
+    };
+
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(
+        |_| RawWaker::new(ptr::null(), &VTABLE),
+        |_| {},
+        |_| {},
+        |_| {},
+    );
+    // SAFETY: the vtable's functions do nothing, so they uphold `RawWaker`'s contract
+    let waker = unsafe { Waker::from_raw(RawWaker::new(ptr::null(), &VTABLE)) };
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => hint::spin_loop(),
+        }
+    }
+}
+
+/// Runs a test on a watchdog thread, failing if it doesn't finish within `timeout`.
+///
+/// A test that times out is left running in the background since threads can't be cancelled.
+///
+/// # Panics
+///
+/// If the test panics or times out.
+#[cfg(feature = "std")]
+pub fn run_with_timeout(name: &str, impl_name: &str, timeout: Duration, test: fn()) {
+    let (sender, receiver) = mpsc::channel();
+    #[cfg(feature = "harness")]
+    let capture = crate::harness::Capture::current();
+    thread::Builder::new()
+        .name(name.into())
+        .spawn(move || {
+            // The test's panics are captured with those of the harness's thread running it
+            #[cfg(feature = "harness")]
+            crate::harness::Capture::set(capture);
+            let result = panic::catch_unwind(test);
+            // The receiver is gone if the test timed out
+            let _ = sender.send(result);
+        })
+        .expect("failed to spawn test thread");
+    match receiver.recv_timeout(timeout) {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => panic::resume_unwind(err),
+        Err(mpsc::RecvTimeoutError::Timeout) => panic!(
+            "associated test `{name}` for impl `{impl_name}` exceeded {}ms",
+            timeout.as_millis()
+        ),
+        Err(mpsc::RecvTimeoutError::Disconnected) => {
+            unreachable!("the test thread sends its result before hanging up")
+        }
+    }
+}
+
+/// Serializes `#[serial]` tests, which take it exclusively, with all other associated tests.
+#[cfg(feature = "std")]
+static SERIAL: RwLock<()> = RwLock::new(());
+
+/// Runs a test while holding [`SERIAL`], exclusively if the test is `serial`.
+#[cfg(feature = "std")]
+pub fn run_locked<R>(serial: bool, test: impl FnOnce() -> R) -> R {
+    // A failed test poisons the lock, but it doesn't protect any data
+    if serial {
+        let _guard = SERIAL.write().unwrap_or_else(PoisonError::into_inner);
+        test()
+    } else {
+        let _guard = SERIAL.read().unwrap_or_else(PoisonError::into_inner);
+        test()
+    }
+}
+
+/// Serializes `#[serial]` tests with all other associated tests without the `std` feature: the
+/// number of tests running, or [`WRITER`] while a `#[serial]` test is running.
+#[cfg(not(feature = "std"))]
+static SERIAL: AtomicUsize = AtomicUsize::new(0);
+#[cfg(not(feature = "std"))]
+const WRITER: usize = usize::MAX;
+
+/// Runs a test while holding [`SERIAL`], exclusively if the test is `serial`, spinning while
+/// it's unavailable.
+#[cfg(not(feature = "std"))]
+pub fn run_locked<R>(serial: bool, test: impl FnOnce() -> R) -> R {
+    /// Releases the lock even if the test panics
+    struct Guard(bool);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            if self.0 {
+                SERIAL.store(0, Ordering::Release);
+            } else {
+                SERIAL.fetch_sub(1, Ordering::Release);
+            }
+        }
+    }
+
+    loop {
+        let running = SERIAL.load(Ordering::Relaxed);
+        let acquired = match (serial, running) {
+            (true, 0) => Some(WRITER),
+            (false, running) if running < WRITER - 1 => Some(running + 1),
+            _ => None,
+        };
+        if let Some(acquired) = acquired {
+            let exchanged = SERIAL.compare_exchange_weak(
+                running,
+                acquired,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            );
+            if exchanged.is_ok() {
+                break;
+            }
+        }
+        core::hint::spin_loop();
+    }
+    let _guard = Guard(serial);
+    test()
+}
+
+/// Runs a test, catching panics and enforcing its timeout (if any), or explains why it was skipped.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn run_case(
+    name: &str,
+    impl_name: &str,
+    timeout: Option<Duration>,
+    serial: bool,
+    test: Result<fn(), Skipped>,
+) -> Outcome {
+    let run = match test {
+        Ok(run) => run,
+        Err(skipped) => {
+            return Outcome::Skipped {
+                reason: skipped.to_string(),
+            }
+        }
+    };
+    let result = run_locked(serial, || match timeout {
+        Some(timeout) => panic::catch_unwind(|| run_with_timeout(name, impl_name, timeout, run)),
+        None => panic::catch_unwind(run),
+    });
+    match result {
+        Ok(()) => Outcome::Passed,
+        Err(err) => Outcome::Failed {
+            message: panic_message(&*err),
+        },
+    }
+}
+
+/// Runs an associated test generated as a standard `#[test]`, or reports that it was skipped,
+/// recording its outcome in the report requested by `TESTED_TRAIT_REPORT` (if any).
+///
+/// `should_panic` and `expected_panic` mirror the test's `#[should_panic]` attribute, which the
+/// harness checks itself; they only decide the status recorded in the report.
+///
+/// # Panics
+///
+/// If the test panics, with its original panic for the harness.
+#[cfg(feature = "std")]
+pub fn run_reported(
+    trait_name: &str,
+    impl_name: &str,
+    should_panic: bool,
+    expected_panic: Option<&str>,
+    test: &Test,
+) {
+    let start = Instant::now();
+    let run = match test.run {
+        Ok(run) => run,
+        Err(skipped) => {
+            reporter().skipped(impl_name, test.name, &skipped);
+            let status = Status::Skipped {
+                reason: skipped.to_string(),
+            };
+            return record_reported(trait_name, impl_name, test.name, status, start);
+        }
+    };
+    let result = run_locked(test.serial, || match test.timeout {
+        Some(timeout) => {
+            panic::catch_unwind(|| run_with_timeout(test.name, impl_name, timeout, run))
+        }
+        None => panic::catch_unwind(run),
+    });
+    let status = match &result {
+        Ok(()) if should_panic => Status::Failed {
+            message: "test did not panic as expected".into(),
+        },
+        Ok(()) => Status::Passed,
+        Err(err) => {
+            let message = panic_message(&**err);
+            match expected_panic {
+                _ if !should_panic => Status::Failed { message },
+                Some(expected) if !message.contains(expected) => Status::Failed {
+                    message: std::format!(
+                        "panic did not contain expected string\n      panic message: \
+                         {message:?}\n expected substring: {expected:?}"
+                    ),
+                },
+                _ => Status::Passed,
+            }
+        }
+    };
+    record_reported(trait_name, impl_name, test.name, status, start);
+    if let Err(err) = result {
+        panic::resume_unwind(err);
+    }
+}
+
+#[cfg(feature = "std")]
+fn record_reported(
+    trait_name: &str,
+    impl_name: &str,
+    name: &'static str,
+    status: Status,
+    start: Instant,
+) {
+    let duration = start.elapsed();
+    report::record_test(
+        trait_name,
+        impl_name,
+        TestReport {
+            name,
+            status,
+            duration,
+        },
+    );
+}
+
+#[cfg(feature = "std")]
+fn run_test(impl_name: &str, test: &Test, run_ignored: bool) -> TestReport {
+    let start = Instant::now();
+    let status = match test.ignore {
+        ignore if ignore.is_ignored() && !run_ignored => Status::Ignored {
+            reason: ignore.reason(),
+        },
+        _ => match run_case(test.name, impl_name, test.timeout, test.serial, test.run) {
+            Outcome::Passed => Status::Passed,
+            Outcome::Failed { message } => Status::Failed { message },
+            Outcome::Skipped { reason } => Status::Skipped { reason },
+        },
+    };
+    TestReport {
+        name: test.name,
+        status,
+        duration: start.elapsed(),
+    }
+}
+
+/// Runs `tests` on a pool of threads, returning their reports in the same order as `tests`.
+#[cfg(feature = "std")]
+fn run_parallel(impl_name: &str, tests: &[&Test], run_ignored: bool) -> Vec<TestReport> {
+    let next = AtomicUsize::new(0);
+    let workers = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(tests.len());
+    let mut reports = (0..tests.len()).map(|_| None).collect::<Vec<_>>();
+    thread::scope(|scope| {
+        let workers = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut reports = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(test) = tests.get(i) else {
+                            break reports;
+                        };
+                        reports.push((i, run_test(impl_name, test, run_ignored)));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for worker in workers {
+            for (i, report) in worker.join().expect("test failures are caught") {
+                reports[i] = Some(report);
+            }
+        }
+    });
+    reports.into_iter().map(Option::unwrap).collect()
+}
+
+/// Which tests run, as requested by the standard test harness's `--ignored` and
+/// `--include-ignored` flags.
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RunIgnored {
+    /// Only the tests that aren't ignored
+    #[default]
+    No,
+    /// Every test, with `--include-ignored`
+    Yes,
+    /// Only the ignored tests, with `--ignored`
+    Only,
+}
+
+#[cfg(feature = "std")]
+impl RunIgnored {
+    /// Which tests run given the arguments the test binary was run with, where the last flag
+    /// wins.
+    fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        args.into_iter()
+            .fold(Self::No, |run_ignored, arg| match arg.as_str() {
+                "--ignored" => Self::Only,
+                "--include-ignored" => Self::Yes,
+                _ => run_ignored,
+            })
+    }
+}
+
+/// Runs every test in `tests`, continuing past failures, and panics with a summary of the
+/// failures (if any) once all tests have run.
+///
+/// If `parallel`, the tests run on a pool of threads and their results are printed in order once
+/// they've all finished. Like the standard test harness, ignored tests only run with
+/// `--include-ignored`, or alone with `--ignored`.
+///
+/// # Panics
+///
+/// If any test fails.
+#[cfg(feature = "std")]
+pub fn run_suite(trait_name: &str, impl_name: &str, parallel: bool, tests: &[Test]) {
+    let run_ignored = RunIgnored::from_args(env::args());
+    run_suite_with(trait_name, impl_name, parallel, tests, run_ignored);
+}
+
+/// Runs every test in `tests` as [`run_suite`] does, given which tests run.
+#[cfg(feature = "std")]
+pub(crate) fn run_suite_with(
+    trait_name: &str,
+    impl_name: &str,
+    parallel: bool,
+    tests: &[Test],
+    run_ignored: RunIgnored,
+) {
+    // Like libtest, the tests that aren't ignored are filtered out with `--ignored`
+    let tests = (tests.iter())
+        .filter(|test| run_ignored != RunIgnored::Only || test.ignore.is_ignored())
+        .collect::<Vec<_>>();
+    let run_ignored = run_ignored != RunIgnored::No;
+    println!(
+        "running {} test{} for impl {impl_name}",
+        tests.len(),
+        if tests.len() == 1 { "" } else { "s" },
+    );
+    let print = |report: &TestReport| {
+        let name = report.name;
+        match &report.status {
+            Status::Passed => println!("test {trait_name}::{name} ... ok"),
+            Status::Failed { .. } => println!("test {trait_name}::{name} ... FAILED"),
+            Status::Ignored {
+                reason: Some(reason),
+            } => println!("test {trait_name}::{name} ... ignored, {reason}"),
+            Status::Ignored { reason: None } => println!("test {trait_name}::{name} ... ignored"),
+            Status::Skipped { reason } => {
+                println!("test {trait_name}::{name} ... skipped ({reason})");
+            }
+        }
+    };
+    let reports = if parallel {
+        let reports = run_parallel(impl_name, &tests, run_ignored);
+        reports.iter().for_each(print);
+        reports
+    } else {
+        (tests.iter())
+            .map(|test| {
+                let report = run_test(impl_name, test, run_ignored);
+                print(&report);
+                report
+            })
+            .collect()
+    };
+
+    let failures = (reports.iter())
+        .filter_map(|report| match &report.status {
+            Status::Failed { message } => Some((report.name, message.clone())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    report::record(SuiteReport {
+        trait_name: String::from(trait_name),
+        impl_name: String::from(impl_name),
+        tests: reports,
+    });
+
+    if !failures.is_empty() {
+        let names = (failures.iter())
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut summary = std::format!(
+            "{} of {} test{} failed for impl {impl_name}: {names}\n",
+            failures.len(),
+            tests.len(),
+            if tests.len() == 1 { "" } else { "s" },
+        );
+        for (name, message) in &failures {
+            use fmt::Write as _;
+            let _ = write!(summary, "\n---- {trait_name}::{name} ----\n{message}\n");
+        }
+        panic!("{summary}");
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn panic_message(err: &(dyn Any + Send)) -> String {
+    if let Some(message) = err.downcast_ref::<String>() {
+        message.clone()
+    } else if let Some(message) = err.downcast_ref::<&str>() {
+        String::from(*message)
+    } else {
+        String::from("test panicked with a non-string value")
+    }
+}
@@ -0,0 +1,57 @@
+//! Associated tests as data, for listing, filtering, and running them from custom harnesses.
+
+use std::string::String;
+
+/// An associated test instantiated for a concrete implementation by [`test_impl`](crate::test_impl).
+///
+/// The tests instantiated for an implementation of a trait annotated with
+/// [`tested_trait`](crate::tested_trait) are available from the trait's `tested_trait_suite()`
+/// method, e.g. `<Bar as Foo>::tested_trait_suite()`.
+#[derive(Debug, Clone, Copy)]
+pub struct TestCase {
+    /// The name of the associated test
+    pub name: &'static str,
+    /// How the test decides whether it passed
+    pub kind: TestKind,
+    /// Whether the test is marked `#[ignore]`
+    pub ignored: bool,
+    /// Why the test is ignored, if a reason was given with `#[ignore = "reason"]`
+    pub ignore_reason: Option<&'static str>,
+    /// Whether the test is marked `#[serial]`
+    pub serial: bool,
+    /// Runs the test, catching panics and enforcing its timeout (if any)
+    ///
+    /// `#[serial]` tests don't run concurrently with other associated tests, even when `run` is
+    /// called from multiple threads.
+    pub run: fn() -> Outcome,
+}
+
+/// How an associated test decides whether it passed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TestKind {
+    /// Passes if it doesn't panic
+    Standard,
+    /// Passes if it returns `Ok`
+    ReturnsResult,
+    /// Passes if it panics, i.e. is marked `#[should_panic]`
+    ShouldPanic,
+}
+
+/// The result of running a [`TestCase`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Outcome {
+    /// The test passed
+    Passed,
+    /// The test failed with a panic
+    Failed {
+        /// The panic's message
+        message: String,
+    },
+    /// The test is `#[optional]` and the implementation doesn't satisfy its bounds
+    Skipped {
+        /// Which bounds the implementation doesn't satisfy
+        reason: String,
+    },
+}
@@ -0,0 +1,64 @@
+//! Runs associated tests with `tested_trait::harness` instead of the standard test harness.
+
+use tested_trait::{test_impl, tested_trait};
+
+#[tested_trait]
+trait Counter {
+    fn new() -> Self;
+    fn increment(&mut self);
+    fn count(&self) -> usize;
+
+    #[test]
+    fn starts_at_zero() {
+        assert_eq!(Self::new().count(), 0);
+    }
+
+    #[test]
+    fn increments() {
+        let mut counter = Self::new();
+        counter.increment();
+        assert_eq!(counter.count(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    #[serial]
+    fn overflows() {
+        let mut counter = Self::new();
+        for _ in 0..=usize::from(u8::MAX) {
+            counter.increment();
+        }
+    }
+
+    #[test]
+    #[optional]
+    fn default_matches_new()
+    where
+        Self: Default,
+    {
+        assert_eq!(Self::default().count(), Self::new().count());
+    }
+
+    #[test]
+    #[ignore = "too slow"]
+    fn slow() {}
+}
+
+struct Count(u8);
+
+#[test_impl]
+impl Counter for Count {
+    fn new() -> Self {
+        Count(0)
+    }
+    fn increment(&mut self) {
+        self.0 = self.0.checked_add(1).unwrap();
+    }
+    fn count(&self) -> usize {
+        self.0.into()
+    }
+}
+
+fn main() {
+    tested_trait::harness::main()
+}
@@ -0,0 +1,185 @@
+//! Associated tests in a `#![no_std]` crate, which compile and run with or without the `std`
+//! feature.
+
+#![no_std]
+
+use core::{
+    fmt::Display,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use tested_trait::{test_impl, tested_trait, Reporter};
+
+#[tested_trait]
+pub trait Stack {
+    fn new() -> Self;
+    fn push(&mut self, value: u8);
+    fn pop(&mut self) -> Option<u8>;
+
+    #[test]
+    fn pop_returns_last_pushed() {
+        let mut stack = Self::new();
+        stack.push(1);
+        stack.push(2);
+        assert_eq!(stack.pop(), Some(2));
+    }
+
+    #[test]
+    fn result_returning() -> Result<(), &'static str> {
+        Self::new()
+            .pop()
+            .map_or(Ok(()), |_| Err("popped from an empty stack"))
+    }
+
+    #[test]
+    #[should_panic = "full"]
+    fn overflows() {
+        let mut stack = Self::new();
+        for value in 0..=u8::MAX {
+            stack.push(value);
+        }
+    }
+
+    #[test]
+    #[optional]
+    fn default_is_empty()
+    where
+        Self: Default,
+    {
+        assert_eq!(Self::default().pop(), None);
+    }
+
+    #[test]
+    #[serial]
+    async fn async_push() {
+        let mut stack = Self::new();
+        async {}.await;
+        stack.push(1);
+        assert_eq!(stack.pop(), Some(1));
+    }
+}
+
+pub struct ArrayStack {
+    items: [u8; 16],
+    len: usize,
+}
+
+#[test_impl]
+impl Stack for ArrayStack {
+    fn new() -> Self {
+        Self {
+            items: [0; 16],
+            len: 0,
+        }
+    }
+    fn push(&mut self, value: u8) {
+        assert!(self.len < self.items.len(), "full");
+        self.items[self.len] = value;
+        self.len += 1;
+    }
+    fn pop(&mut self) -> Option<u8> {
+        self.len = self.len.checked_sub(1)?;
+        Some(self.items[self.len])
+    }
+}
+
+#[tested_trait]
+pub trait Counter {
+    fn count(&self) -> usize;
+
+    #[test]
+    fn starts_at_zero()
+    where
+        Self: Default,
+    {
+        assert_eq!(Self::default().count(), 0);
+    }
+
+    #[test]
+    #[optional]
+    fn clones_count()
+    where
+        Self: Default + Clone,
+    {
+        let counter = Self::default();
+        assert_eq!(counter.clone().count(), counter.count());
+    }
+
+    #[test]
+    #[ignore]
+    fn ignored() {}
+}
+
+struct CountingReporter;
+
+static STARTED: AtomicUsize = AtomicUsize::new(0);
+static PASSED: AtomicUsize = AtomicUsize::new(0);
+static IGNORED: AtomicUsize = AtomicUsize::new(0);
+static SKIPPED: AtomicUsize = AtomicUsize::new(0);
+
+impl Reporter for CountingReporter {
+    fn started(&self, _impl_name: &str, _test: &str) {
+        STARTED.fetch_add(1, Ordering::Relaxed);
+    }
+    fn passed(&self, _impl_name: &str, _test: &str) {
+        PASSED.fetch_add(1, Ordering::Relaxed);
+    }
+    fn ignored(&self, _impl_name: &str, _test: &str, _reason: Option<&str>) {
+        IGNORED.fetch_add(1, Ordering::Relaxed);
+    }
+    fn skipped(&self, impl_name: &str, test: &str, reason: &dyn Display) {
+        // `Stack for ArrayStack`'s tests may be running concurrently
+        if impl_name != "Counter for Count" {
+            return;
+        }
+        assert_eq!(test, "clones_count");
+        let mut buf = Buf([0; 64], 0);
+        core::fmt::write(&mut buf, format_args!("{reason}")).unwrap();
+        assert_eq!(
+            &buf.0[..buf.1],
+            b"the implementation does not satisfy `Self: Clone`"
+        );
+        SKIPPED.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+struct Buf([u8; 64], usize);
+
+impl core::fmt::Write for Buf {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let end = self.1 + s.len();
+        self.0
+            .get_mut(self.1..end)
+            .ok_or(core::fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.1 = end;
+        Ok(())
+    }
+}
+
+#[test]
+// The implementation's tests run where it's declared, after the reporter is set
+#[allow(clippy::items_after_statements)]
+fn reports_inline_tests() {
+    tested_trait::set_reporter(&CountingReporter).unwrap();
+    assert!(tested_trait::set_reporter(&CountingReporter).is_err());
+
+    #[derive(Default)]
+    struct Count(usize);
+
+    #[test_impl]
+    #[in_integration_test]
+    impl Counter for Count {
+        fn count(&self) -> usize {
+            self.0
+        }
+    }
+
+    // With `std`, the suite is run by `run_suite`, which prints its progress instead
+    if !cfg!(feature = "std") {
+        assert_eq!(STARTED.load(Ordering::Relaxed), 1);
+        assert_eq!(PASSED.load(Ordering::Relaxed), 1);
+        assert_eq!(IGNORED.load(Ordering::Relaxed), 1);
+        assert_eq!(SKIPPED.load(Ordering::Relaxed), 1);
+    }
+}
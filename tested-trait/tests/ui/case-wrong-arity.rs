@@ -0,0 +1,15 @@
+use tested_trait::tested_trait;
+
+#[tested_trait]
+trait Foo {
+    fn square(x: u32) -> u32;
+
+    #[test]
+    #[case(0, 0)]
+    #[case(4)]
+    fn squares(x: u32, expected: u32) {
+        assert_eq!(Self::square(x), expected);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+use tested_trait::{test_impl, tested_trait};
+
+#[tested_trait]
+trait Foo {
+    fn clone_of<T: Clone>(item: &T) -> T;
+
+    #[test]
+    fn clones<U: Clone + Default + PartialEq + std::fmt::Debug>() {
+        assert_eq!(Self::clone_of(&U::default()), U::default());
+    }
+}
+
+struct Bar;
+
+#[test_impl]
+impl Foo for Bar {
+    fn clone_of<T: Clone>(item: &T) -> T {
+        item.clone()
+    }
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use tested_trait::tested_trait;
+
+#[tested_trait]
+trait Foo {}
+
+#[tested_trait]
+trait Bar {}
+
+#[tested_trait(include_supertraits(Bar))]
+trait Baz: Foo {}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use tested_trait::tested_trait;
+
+fn main() {
+    #[tested_trait]
+    trait Foo {
+        #[test]
+        #[ignore(reason = "slow")]
+        fn test() {}
+    }
+}
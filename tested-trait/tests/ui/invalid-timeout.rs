@@ -0,0 +1,20 @@
+use tested_trait::tested_trait;
+
+fn main() {
+    #[tested_trait(timeout = "500ms")]
+    trait Foo {}
+
+    #[tested_trait]
+    trait Bar {
+        #[test]
+        #[timeout(seconds = 1)]
+        fn test() {}
+    }
+
+    #[tested_trait]
+    trait Baz {
+        #[test]
+        #[timeout = "1s"]
+        fn test() {}
+    }
+}
@@ -0,0 +1,16 @@
+// The feature isn't declared since this crate has no manifest of its own
+#![allow(unexpected_cfgs)]
+
+use tested_trait::{test_impl, tested_trait};
+
+#[tested_trait(feature = "conformance-tests")]
+trait Foo {
+    #[test]
+    fn foo() {}
+}
+
+// Builds without the feature, since the tests are only needed by test builds
+#[test_impl]
+impl Foo for () {}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use tested_trait::tested_trait;
+
+#[tested_trait(remote = std::io::Read)]
+trait ReadTests {
+    fn read_all(&mut self) -> Vec<u8>;
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+// The feature isn't declared since this crate has no manifest of its own
+#![allow(unexpected_cfgs)]
+
+use tested_trait::{test_impl, tested_trait};
+
+#[tested_trait(feature = "conformance-tests")]
+trait Foo {
+    #[test]
+    fn foo() {}
+}
+
+fn main() {
+    #[test_impl]
+    #[in_integration_test]
+    impl Foo for () {}
+}
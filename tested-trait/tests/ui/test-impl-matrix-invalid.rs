@@ -0,0 +1,22 @@
+use tested_trait::{test_impl, tested_trait};
+
+#[tested_trait]
+trait Foo<T> {
+    #[test]
+    fn test() {}
+}
+
+struct Bar<T, const N: usize>([T; N]);
+
+#[test_impl(T in [u8, u16])]
+impl<T, const N: usize> Foo<T> for Bar<T, N> {}
+
+struct Baz<T>(T);
+
+#[test_impl(const T in [1, 2])]
+impl<T> Foo<T> for Baz<T> {}
+
+#[test_impl(U in [u8])]
+impl<T> Foo<T> for Option<T> {}
+
+fn main() {}
@@ -0,0 +1,35 @@
+use tested_trait::{test_impl, tested_trait};
+
+#[tested_trait]
+trait Wrapper<T> {
+    fn wrap(value: T) -> Self;
+
+    #[test]
+    fn test() {}
+}
+
+#[tested_trait]
+trait Other {}
+
+#[test_impl(Vec<u32>: Wrapper<u32>)]
+impl<T> Wrapper<T> for Option<T> {
+    fn wrap(value: T) -> Self {
+        Some(value)
+    }
+}
+
+#[test_impl(Result<u32, ()>: Wrapper<u8>)]
+impl<T> Wrapper<T> for Result<T, ()> {
+    fn wrap(value: T) -> Self {
+        Ok(value)
+    }
+}
+
+#[test_impl(Box<u32>: Other)]
+impl<T> Wrapper<T> for Box<T> {
+    fn wrap(value: T) -> Self {
+        Box::new(value)
+    }
+}
+
+fn main() {}
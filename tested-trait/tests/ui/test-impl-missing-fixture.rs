@@ -0,0 +1,28 @@
+use tested_trait::{test_impl, tested_trait};
+
+#[tested_trait]
+trait Counter {
+    fn increment(&mut self);
+    fn get(&self) -> u32;
+
+    #[test]
+    fn increment_adds_one(&mut self) {
+        let before = self.get();
+        self.increment();
+        assert_eq!(self.get(), before + 1);
+    }
+}
+
+struct Bar(u32);
+
+#[test_impl]
+impl Counter for Bar {
+    fn increment(&mut self) {
+        self.0 += 1;
+    }
+    fn get(&self) -> u32 {
+        self.0
+    }
+}
+
+fn main() {}
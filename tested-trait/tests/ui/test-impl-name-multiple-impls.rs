@@ -0,0 +1,9 @@
+use tested_trait::{test_impl, tested_trait};
+
+#[tested_trait]
+trait Foo {}
+
+#[test_impl(name = "foo", u8: Foo, u16: Foo)]
+impl<T> Foo for T {}
+
+fn main() {}
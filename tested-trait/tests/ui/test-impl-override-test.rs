@@ -0,0 +1,22 @@
+use tested_trait::{test_impl, tested_trait};
+
+#[tested_trait]
+trait Foo {
+    fn foo() -> bool;
+
+    #[test]
+    fn foo_is_true() {
+        assert!(Self::foo());
+    }
+}
+
+#[test_impl]
+impl Foo for () {
+    fn foo() -> bool {
+        false
+    }
+
+    fn __tested_trait_test_foo_is_true() {}
+}
+
+fn main() {}
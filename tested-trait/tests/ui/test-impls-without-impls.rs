@@ -0,0 +1,15 @@
+use tested_trait::{test_impls, tested_trait};
+
+#[tested_trait]
+trait Foo {
+    #[test]
+    fn test() {}
+}
+
+impl Foo for () {}
+
+test_impls!();
+
+test_impls!(#[ignore] (): Foo);
+
+fn main() {}
@@ -0,0 +1,11 @@
+use tested_trait::tested_trait;
+
+#[tested_trait]
+trait Foo {
+    #[test]
+    fn test(x: u32) {
+        assert!(x < 10);
+    }
+}
+
+fn main() {}
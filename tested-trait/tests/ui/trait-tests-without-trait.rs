@@ -0,0 +1,9 @@
+use tested_trait::trait_tests;
+
+#[trait_tests]
+mod foo {
+    #[test]
+    fn test() {}
+}
+
+fn main() {}